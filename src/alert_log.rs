@@ -0,0 +1,100 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use crate::alerts::ActiveAlert;
+
+const MAX_BYTES: u64 = 1024 * 1024; // Rotate once the live file would grow past this...
+const KEEP_ROTATED: usize = 3;      // ...keeping alerts.log.1 (newest) to .3 (oldest)
+
+// --alert-log: one key=value line per alert transition, e.g.
+// `2026-03-01T14:02:11.532+01:00 event=fired metric=cpu value=93.4 threshold=90 unit=%`
+pub struct AlertLog {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+}
+
+impl AlertLog {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        Self::with_limit(path, MAX_BYTES)
+    }
+
+    fn with_limit(path: &Path, max_bytes: u64) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path: path.to_path_buf(), file, size, max_bytes })
+    }
+
+    pub fn fired(&mut self, alert: &ActiveAlert) -> io::Result<()> {
+        self.record("fired", alert)
+    }
+
+    pub fn cleared(&mut self, alert: &ActiveAlert) -> io::Result<()> {
+        self.record("cleared", alert)
+    }
+
+    fn record(&mut self, event: &str, alert: &ActiveAlert) -> io::Result<()> {
+        let metric = alert.rule.metric;
+        let line = format!(
+            "{} event={} metric={} value={:.1} threshold={} unit={}\n",
+            chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f%:z"),
+            event, metric.label().to_lowercase(), alert.value, alert.rule.threshold, metric.unit()
+        );
+        if self.size > 0 && self.size + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.file.flush()?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    // alerts.log -> .1 -> .2 -> ... and the oldest falls off the end
+    fn rotate(&mut self) -> io::Result<()> {
+        for i in (1..KEEP_ROTATED).rev() {
+            let from = rotated(&self.path, i);
+            if from.exists() {
+                fs::rename(&from, rotated(&self.path, i + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated(&self.path, 1))?;
+        self.file = File::create(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn rotated(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use crate::alerts::{AlertMetric, AlertRule};
+
+    #[test]
+    fn rotates_at_the_size_cap() {
+        let dir = std::env::temp_dir().join(format!("mk05-alert-log-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("alerts.log");
+        let rule = AlertRule { metric: AlertMetric::Temp, threshold: 85.0, sustain: Duration::ZERO };
+        let alert = ActiveAlert { rule: &rule, value: 91.25 };
+
+        let mut log = AlertLog::with_limit(&path, 150).unwrap();
+        for _ in 0..5 {
+            log.fired(&alert).unwrap();
+            log.cleared(&alert).unwrap();
+        }
+        let live = fs::read_to_string(&path).unwrap();
+        assert!(live.len() <= 150);
+        assert!(live.lines().last().unwrap().ends_with("event=cleared metric=temp value=91.2 threshold=85 unit=°C"));
+        assert!(rotated(&path, KEEP_ROTATED).exists());
+        assert!(!rotated(&path, KEEP_ROTATED + 1).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,184 @@
+use std::time::{Duration, Instant};
+use anyhow::{bail, Context, Result};
+use crate::monitor::SystemStats;
+use crate::units::TempUnit;
+
+// A firing rule only clears once the value drops this far (in the metric's unit)
+// below the threshold, so readings hovering at the boundary don't flicker.
+const HYSTERESIS: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertMetric {
+    Cpu,  // Total CPU %
+    Ram,  // Used RAM %
+    Temp, // Hottest sensor °C
+    Disk, // Fullest disk %
+}
+
+impl AlertMetric {
+    pub fn parse(name: &str) -> Result<Self> {
+        Ok(match name.to_lowercase().as_str() {
+            "cpu" => AlertMetric::Cpu,
+            "ram" | "mem" => AlertMetric::Ram,
+            "temp" => AlertMetric::Temp,
+            "disk" => AlertMetric::Disk,
+            other => bail!("unknown alert metric '{}' (expected cpu, ram, temp or disk)", other),
+        })
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AlertMetric::Cpu => "CPU",
+            AlertMetric::Ram => "RAM",
+            AlertMetric::Temp => "TEMP",
+            AlertMetric::Disk => "DISK",
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            AlertMetric::Cpu | AlertMetric::Ram | AlertMetric::Disk => "%",
+            AlertMetric::Temp => "°C",
+        }
+    }
+
+    // Values are always held in the metric's own unit; temperatures are only
+    // converted here, for display
+    pub fn format(self, value: f64, temp_unit: TempUnit) -> String {
+        match self {
+            AlertMetric::Temp => temp_unit.format(value),
+            _ => format!("{:.0}{}", value, self.unit()),
+        }
+    }
+
+    fn value(self, stats: &SystemStats) -> Option<f64> {
+        match self {
+            AlertMetric::Cpu => Some(stats.total_cpu_usage as f64),
+            AlertMetric::Ram if stats.ram_total > 0 => Some(stats.ram_used as f64 / stats.ram_total as f64 * 100.0),
+            AlertMetric::Ram => None,
+            AlertMetric::Temp => stats.temperatures.iter().map(|t| t.celsius as f64).reduce(f64::max),
+            AlertMetric::Disk => stats.disks.iter().filter_map(|d| d.usage_pct()).reduce(f64::max),
+        }
+    }
+}
+
+// "metric above threshold for at least `sustain`"
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub metric: AlertMetric,
+    pub threshold: f64,
+    pub sustain: Duration,
+}
+
+impl AlertRule {
+    // Parses `metric:threshold[:seconds]`, e.g. `cpu:90:5`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut parts = spec.split(':');
+        let metric = AlertMetric::parse(parts.next().unwrap_or(""))?;
+        let threshold: f64 = parts.next().context("alert rule is missing a threshold")?
+            .parse().with_context(|| format!("invalid alert threshold in '{}'", spec))?;
+        let sustain = match parts.next() {
+            Some(secs) => {
+                let secs: f64 = secs.parse().with_context(|| format!("invalid alert duration in '{}'", spec))?;
+                match Duration::try_from_secs_f64(secs) {
+                    Ok(sustain) => sustain,
+                    Err(_) => bail!("alert duration in '{}' must be a finite number of seconds, 0 or more", spec),
+                }
+            }
+            None => Duration::ZERO,
+        };
+        if parts.next().is_some() {
+            bail!("too many fields in alert rule '{}'", spec);
+        }
+        Ok(Self { metric, threshold, sustain })
+    }
+
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            AlertRule { metric: AlertMetric::Cpu, threshold: 90.0, sustain: Duration::from_secs(5) },
+            AlertRule { metric: AlertMetric::Ram, threshold: 90.0, sustain: Duration::from_secs(5) },
+            AlertRule { metric: AlertMetric::Temp, threshold: 85.0, sustain: Duration::from_secs(5) },
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct RuleState {
+    above_since: Option<Instant>, // Condition has held continuously since
+    firing: bool,
+    value: f64,                   // Latest observed value
+}
+
+pub struct ActiveAlert<'a> {
+    pub rule: &'a AlertRule,
+    pub value: f64,
+}
+
+// Evaluates every rule against each incoming sample. A rule only fires once its
+// condition has held for the rule's `sustain`, so brief spikes are ignored.
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    state: Vec<RuleState>,
+    cleared: Vec<usize>, // Rules that stopped firing on the latest sample
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        let state = vec![RuleState::default(); rules.len()];
+        Self { rules, state, cleared: Vec::new() }
+    }
+
+    // Returns the alerts that started firing on this sample
+    pub fn evaluate(&mut self, stats: &SystemStats, now: Instant) -> Vec<ActiveAlert<'_>> {
+        let mut newly_fired = Vec::new();
+        self.cleared.clear();
+        for (i, (rule, state)) in self.rules.iter().zip(self.state.iter_mut()).enumerate() {
+            let Some(value) = rule.metric.value(stats) else { continue };
+            state.value = value;
+
+            if value > rule.threshold {
+                let since = *state.above_since.get_or_insert(now);
+                if !state.firing && now.duration_since(since) >= rule.sustain {
+                    state.firing = true;
+                    newly_fired.push(ActiveAlert { rule, value });
+                }
+            } else if !state.firing || value <= rule.threshold - HYSTERESIS {
+                if state.firing {
+                    self.cleared.push(i);
+                }
+                state.above_since = None;
+                state.firing = false;
+            }
+        }
+        newly_fired
+    }
+
+    pub fn active(&self) -> impl Iterator<Item = ActiveAlert<'_>> {
+        self.rules.iter().zip(self.state.iter())
+            .filter(|(_, s)| s.firing)
+            .map(|(rule, s)| ActiveAlert { rule, value: s.value })
+    }
+
+    // Alerts that stopped firing on the last `evaluate`, with the value that cleared them
+    pub fn cleared(&self) -> impl Iterator<Item = ActiveAlert<'_>> {
+        self.cleared.iter().map(|&i| ActiveAlert { rule: &self.rules[i], value: self.state[i].value })
+    }
+
+    pub fn is_firing(&self, metric: AlertMetric) -> bool {
+        self.active().any(|a| a.rule.metric == metric)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rule_parse_rejects_unusable_durations() {
+        let rule = AlertRule::parse("cpu:90:1.5").unwrap();
+        assert_eq!((rule.threshold, rule.sustain), (90.0, Duration::from_millis(1500)));
+        assert!(AlertRule::parse("cpu:90:-1").is_err());
+        assert!(AlertRule::parse("cpu:90:nan").is_err());
+        assert!(AlertRule::parse("cpu:90:inf").is_err());
+    }
+}
@@ -1,28 +1,400 @@
-use std::collections::VecDeque;
-use std::time::Instant;
-use crate::monitor::{SystemStats, ProcessInfo};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crossbeam_channel::Sender;
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use crate::alert_log::AlertLog;
+use crate::alerts::AlertEngine;
+use crate::diagnostics::Diagnostics;
+use crate::disk_health::DiskHealth;
+use crate::keys::{self, Action};
+use crate::notify::Notifier;
+use crate::report;
+use crate::state::{HistorySnapshot, HISTORY_VERSION};
+use crate::theme::{self, ColorSupport, Palette, Theme};
+use crate::topology::CoreType;
+use crate::units::{TempUnit, UnitMode};
+use crate::monitor::{CpuBreakdown, DiskInfo, DiskIo, KillSignal, MonitorCommand, SystemStats, ProcessInfo, TempInfo};
+use crate::sockets::Connection;
+
+const FOOTER_MESSAGE_SECS: u64 = 8;
+const QUIT_CONFIRM_SECS: u64 = 2;
+const HEATMAP_WIDTH: usize = 100; // Columns of per-core history until the first frame is drawn
+const DEFAULT_SMOOTHING_ALPHA: f64 = 0.3;
+pub const DEFAULT_MOVING_AVERAGE: usize = 5; // Chart points per moving-average window
+const SWAP_RATE_WINDOW_SECS: f64 = 5.0;
+const SWAP_PRESSURE_PCT: f64 = 50.0;           // Swap this full...
+const SWAP_PRESSURE_RATE: f64 = 256.0 * 1024.0; // ...and growing at least this fast (B/s)
+const PROCESS_HISTORY_LEN: usize = 60;          // Per-process CPU samples kept...
+const PROCESS_HISTORY_INTERVAL_SECS: f64 = 1.0; // ...one per second
+const CHART_INTERVAL_SECS: f64 = 0.1; // One chart point per interval
+const STALE_AFTER: Duration = Duration::from_secs(5); // No sample for this long: the monitor is presumed hung
+const RESTRICTED_WARN_SHARE: f64 = 0.25; // Warn at startup when this share of processes can't be read
+const CHART_SPANS_SECS: [u64; 6] = [10, 20, 30, 60, 120, 300]; // Zoom levels for '+'/'-'
+const DEFAULT_CHART_SPAN: usize = 1;
+// Enough points for the widest zoom level; narrower spans only render a tail
+pub const CHART_HISTORY_LEN: usize = (CHART_SPANS_SECS[CHART_SPANS_SECS.len() - 1] as f64 / CHART_INTERVAL_SECS) as usize;
+pub const STACKED_SECTIONS: usize = 6; // Panels in the narrow single-column layout
+pub const TEMP_OVERLAY_COUNT: usize = 3; // Sensors overlaid in the "hottest" temperature view
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessSortKey {
+    Cpu,
+    Mem,
+    Disk, // Read + write throughput
+    Pid,
+    Name,
+}
+
+impl ProcessSortKey {
+    pub fn next(self) -> Self {
+        match self {
+            ProcessSortKey::Cpu => ProcessSortKey::Mem,
+            ProcessSortKey::Mem => ProcessSortKey::Disk,
+            ProcessSortKey::Disk => ProcessSortKey::Pid,
+            ProcessSortKey::Pid => ProcessSortKey::Name,
+            ProcessSortKey::Name => ProcessSortKey::Cpu,
+        }
+    }
+
+    // Usage columns read best biggest-first, identifiers smallest-first
+    pub fn default_ascending(self) -> bool {
+        matches!(self, ProcessSortKey::Pid | ProcessSortKey::Name)
+    }
+
+    // Ascending order; callers reverse for descending
+    pub fn compare(self, a: &ProcessInfo, b: &ProcessInfo) -> std::cmp::Ordering {
+        match self {
+            ProcessSortKey::Cpu => a.cpu.partial_cmp(&b.cpu).unwrap_or(std::cmp::Ordering::Equal),
+            ProcessSortKey::Mem => a.mem.cmp(&b.mem),
+            ProcessSortKey::Disk => (a.disk_read + a.disk_write).cmp(&(b.disk_read + b.disk_write)),
+            ProcessSortKey::Pid => a.pid.cmp(&b.pid),
+            ProcessSortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        }
+    }
+}
+
+// The time-series charts, for settings that apply to some of them (--moving-average-charts)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartId {
+    Cpu,
+    Ram,
+    Net,
+    Temp,
+}
+
+impl ChartId {
+    pub const ALL: [ChartId; 4] = [ChartId::Cpu, ChartId::Ram, ChartId::Net, ChartId::Temp];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ChartId::Cpu => "cpu",
+            ChartId::Ram => "ram",
+            ChartId::Net => "net",
+            ChartId::Temp => "temp",
+        }
+    }
+
+    // "cpu,net" -> those charts
+    pub fn parse_list(spec: &str) -> anyhow::Result<Vec<ChartId>> {
+        let charts = spec.split(',').map(str::trim).filter(|s| !s.is_empty()).map(|name| {
+            ChartId::ALL.into_iter().find(|c| c.name().eq_ignore_ascii_case(name)).ok_or_else(|| {
+                let names: Vec<_> = ChartId::ALL.iter().map(|c| c.name()).collect();
+                anyhow::anyhow!("unknown chart '{}' (expected any of: {})", name, names.join(", "))
+            })
+        }).collect::<anyhow::Result<Vec<_>>>()?;
+        if charts.is_empty() {
+            anyhow::bail!("--moving-average-charts needs at least one chart");
+        }
+        Ok(charts)
+    }
+}
+
+// Which list j/k and the paging keys drive; cycled with Tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusPanel {
+    Processes,
+    Disks,
+    Connections, // Only while the connections table is open
+    Storage,     // Only while the storage table is open
+}
+
+// Order of the storage table ('D'), cycled with 's' while it has focus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskSortKey {
+    Usage,    // Fullest first
+    Capacity, // Largest first
+    Mount,
+}
+
+impl DiskSortKey {
+    pub fn next(self) -> Self {
+        match self {
+            DiskSortKey::Usage => DiskSortKey::Capacity,
+            DiskSortKey::Capacity => DiskSortKey::Mount,
+            DiskSortKey::Mount => DiskSortKey::Usage,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DiskSortKey::Usage => "USE%",
+            DiskSortKey::Capacity => "SIZE",
+            DiskSortKey::Mount => "MOUNT",
+        }
+    }
+}
+
+// Process table columns, chosen with --columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Pid,
+    Name,
+    Cpu,
+    Mem,
+    Disk,
+    User,
+    State,
+    Start,
+}
+
+pub const DEFAULT_COLUMNS: &[Column] = &[Column::Pid, Column::Name, Column::Cpu, Column::Mem, Column::Disk];
+
+impl Column {
+    pub const ALL: [Column; 8] = [
+        Column::Pid, Column::Name, Column::Cpu, Column::Mem, Column::Disk, Column::User, Column::State, Column::Start,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Column::Pid => "pid",
+            Column::Name => "name",
+            Column::Cpu => "cpu",
+            Column::Mem => "mem",
+            Column::Disk => "disk",
+            Column::User => "user",
+            Column::State => "state",
+            Column::Start => "start",
+        }
+    }
+
+    // "pid,name,cpu" -> columns in that order
+    pub fn parse_list(spec: &str) -> anyhow::Result<Vec<Column>> {
+        let columns = spec.split(',').map(str::trim).filter(|s| !s.is_empty()).map(|name| {
+            Column::ALL.into_iter().find(|c| c.name().eq_ignore_ascii_case(name)).ok_or_else(|| {
+                let names: Vec<_> = Column::ALL.iter().map(|c| c.name()).collect();
+                anyhow::anyhow!("unknown column '{}' (expected any of: {})", name, names.join(", "))
+            })
+        }).collect::<anyhow::Result<Vec<_>>>()?;
+        if columns.is_empty() {
+            anyhow::bail!("--columns needs at least one column");
+        }
+        Ok(columns)
+    }
+
+    // Header label, also used for click-to-sort
+    pub fn label(self) -> &'static str {
+        match self {
+            Column::Pid => "PID",
+            Column::Name => "NAME",
+            Column::Cpu => "CPU",
+            Column::Mem => "MEM",
+            Column::Disk => "DISK",
+            Column::User => "USER",
+            Column::State => "STATE",
+            Column::Start => "START",
+        }
+    }
+
+    pub fn sort_key(self) -> Option<ProcessSortKey> {
+        match self {
+            Column::Pid => Some(ProcessSortKey::Pid),
+            Column::Name => Some(ProcessSortKey::Name),
+            Column::Cpu => Some(ProcessSortKey::Cpu),
+            Column::Mem => Some(ProcessSortKey::Mem),
+            Column::Disk => Some(ProcessSortKey::Disk),
+            Column::User | Column::State | Column::Start => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayMode {
+    Full,    // Process table + chart dashboard
+    Compact, // One sparkline row per metric and a two-line process list
+}
+
+// What grouped rows share: the process name ('n') or the owner ('u')
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupKey {
+    #[default]
+    Name,
+    User,
+}
+
+impl GroupKey {
+    // Row label; processes without a resolvable owner share "?"
+    fn label(self, p: &ProcessInfo) -> &str {
+        match self {
+            GroupKey::Name => &p.name,
+            GroupKey::User => p.user.as_deref().unwrap_or("?"),
+        }
+    }
+}
+
+// What the temperature chart plots, cycled with 'e'
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum TempView {
+    #[default]
+    Max,            // Hottest reading across all sensors
+    Sensor(String), // One component, by label
+    Hottest,        // The TEMP_OVERLAY_COUNT currently hottest sensors overlaid
+}
+
+// One line of the process tree. `process.cpu`/`process.mem` hold the subtree totals.
+#[derive(Debug, Clone)]
+pub struct TreeRow {
+    pub depth: usize,
+    pub process: ProcessInfo,
+}
+
+// Processes sharing a name or owner, shown as one row. `process` carries the summed
+// usage (the lowest PID, and the group label as its name); `pids` lists the members
+// in sort order.
+#[derive(Debug, Clone)]
+pub struct ProcessGroup {
+    pub process: ProcessInfo,
+    pub pids: Vec<u32>,
+}
+
+// Signal picker for one process, opened with 'x'
+#[derive(Debug, Clone)]
+pub struct KillMenu {
+    pub pid: u32,
+    pub name: String,
+    pub selected: usize, // Index into KillSignal::ALL
+}
+
+// Min/max/mean of a series since launch (or the last reset), kept incrementally
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunningStats {
+    pub min: f64,
+    pub max: f64,
+    sum: f64,
+    count: u64,
+}
+
+impl RunningStats {
+    pub fn push(&mut self, value: f64) {
+        if self.count == 0 {
+            (self.min, self.max) = (value, value);
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    pub fn avg(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+}
+
+// Where the process table landed on the last frame, for mouse hit-testing
+#[derive(Debug, Clone, Default)]
+pub struct ProcessTableLayout {
+    pub area: Rect,                           // Header row + body
+    pub offset: usize,                        // First visible row after scrolling
+    pub rows: usize,                          // Rows the table was given (the flat list is capped)
+    pub columns: Vec<(ProcessSortKey, Rect)>, // Header cells, clickable to sort
+}
+
+// Header row plus its bottom margin
+const PROCESS_TABLE_HEADER_ROWS: u16 = 2;
 
 pub struct App {
     pub should_quit: bool,
+    pub confirm_quit: bool,               // Require a second 'q' to exit
+    pub pending_quit: Option<Instant>,    // When the first 'q' was pressed
     
     // Charts History (Global)
     pub cpu_history_total: VecDeque<(f64, f64)>, 
+    pub cpu_history_min: VecDeque<(f64, f64)>, // Spread of the raw samples behind each CPU point
+    pub cpu_history_max: VecDeque<(f64, f64)>,
+    pub cpu_stack_history: [VecDeque<(f64, f64)>; 4], // Running totals: user, +system, +iowait, +steal
     pub ram_history: VecDeque<(f64, f64)>,
     pub net_rx_history: VecDeque<(f64, f64)>,
     pub net_tx_history: VecDeque<(f64, f64)>,
     pub temp_history: VecDeque<(f64, f64)>, // Max Temp History
+    pub sensor_history: BTreeMap<String, VecDeque<(f64, f64)>>, // Per component, by label
+    // Since launch, reset with 'r'
+    pub cpu_summary: RunningStats,
+    pub net_rx_summary: RunningStats,
+    pub net_tx_summary: RunningStats,
+    pub temp_summary: RunningStats,
     
+    // Unsmoothed averages behind the charts above, so smoothing can be toggled
+    // without waiting for history to refill
+    cpu_raw: VecDeque<(f64, f64)>,
+    ram_raw: VecDeque<(f64, f64)>,
+    net_rx_raw: VecDeque<(f64, f64)>,
+    net_tx_raw: VecDeque<(f64, f64)>,
+
+    // Smoothing: EMA factor (1.0 = raw) and whether it's currently applied
+    pub smoothing_alpha: f64,
+    pub smoothing_enabled: bool,
+
+    // Moving average over the rendered points only; the stored series stay as sampled
+    pub moving_average_window: usize,
+    pub moving_average_charts: Vec<ChartId>,
+    pub moving_average_enabled: bool,
+
+    pub chart_fill: bool, // Shade the area under chart lines ('F', --fill)
+
     // HEATMAP DATA: Per-core history [CoreIndex][TimeStep]
     // Storing as u8 (0-100) to save memory
     pub cpu_core_history: Vec<VecDeque<u8>>, 
+    pub cpu_core_ids: Vec<usize>, // Core number of each heatmap row
+    pub core_types: HashMap<usize, CoreType>, // P/E classification by core id (empty if not hybrid)
+    // Columns the heatmap canvas had on the last frame (0 = not drawn yet).
+    // Written by the renderer, which only gets `&App`.
+    pub heatmap_columns: Cell<usize>,
 
     // Snapshot Data
     pub processes: Vec<ProcessInfo>,
-    pub disks: Vec<(String, u64, u64)>,
-    pub temps: Vec<(String, f32)>,
+    pub all_processes: Arc<Vec<ProcessInfo>>, // Unfiltered list from the last slow tick
+    pub disks: Vec<DiskInfo>,
+    pub disk_io: Vec<DiskIo>,
+    pub disk_busy: Vec<(String, f32)>,
+    pub disk_health: Vec<(String, DiskHealth)>,
+    pub disk_scroll: usize,     // First disk row shown ('[' / ']')
+    pub disk_rows: Cell<usize>, // Disk rows that fit on the last frame, written by the renderer
+    pub temps: Vec<TempInfo>,
+    pub sockets: Vec<Connection>,
     pub last_stats: Option<SystemStats>,
+    swap_samples: VecDeque<(Instant, u64)>, // Recent swap_used readings for the growth rate
+    pub swap_rate: f64,                      // B/s, positive while swap is filling
+    pub net_session_rx: u64, // Bytes moved since launch
+    pub net_session_tx: u64,
+    net_last_counters: Option<(u64, u64)>,
+    pub session_start: Instant,
+    pub peak_cpu: f32, // Highest total CPU % in any sample since launch (not reset by 'r')
+    pub peak_ram: f64, // Highest RAM use %, same
+    // Recent CPU per PID for the detail overlay, with the start time that tells a
+    // reused PID apart from the process that had it before
+    process_cpu_history: HashMap<u32, (u64, VecDeque<f32>)>,
+    last_process_sample: Option<Instant>,
 
-    pub max_history_len: usize,
+    pub chart_history_len: usize,               // Points kept per chart series (--chart-history)
+    pub heatmap_fixed_width: Option<usize>,     // --heatmap-width; None follows the canvas
     
     // Aggregation
     accumulated_stats: Vec<SystemStats>,
@@ -31,61 +403,491 @@ pub struct App {
 
     // Interaction
     pub process_scroll_state: usize, // Selected row index
-    pub process_sort_by_cpu: bool,   // Toggle sort mode
+    pub process_sort_key: ProcessSortKey, // Cycled with 's'
+    pub process_sort_ascending: bool,     // Reversed with 'S'
+    pub sort_locked: bool,                // 'l': keep rows where they are between samples
+    pub process_tree_view: bool,     // Flat list vs parent/child tree
+    pub process_tree: Vec<TreeRow>,
+    pub tree_scroll_state: usize,
+    pub process_grouped: bool,       // One row per process name ('n') or user ('u')
+    pub group_key: GroupKey,
+    pub process_groups: Vec<ProcessGroup>,
+    pub group_scroll_state: usize,
+    pub user_filter: Option<String>, // Only this owner's processes (--user, or 'o' on a row)
+    pub hide_kernel: bool,           // Leave out kernel threads and idle processes (--hide-kernel, 'K')
+    pub pinned: HashSet<String>,     // Process names listed first whatever the sort ('P'); names outlive PIDs
+    pub process_table_layout: RefCell<ProcessTableLayout>, // Written by the renderer
+    pub columns: Vec<Column>,        // --columns
+    pub process_limit: Option<usize>, // --top: rows in the flat list (None = every process, scrolled)
+    pub irix_mode: bool,             // Process CPU% per core (can pass 100%) vs share of the whole machine; 'I'
+    pub display_mode: DisplayMode,   // Toggled with 'v'
+    pub page_scroll: usize,          // First section shown in the narrow layout (Ctrl-e / Ctrl-y)
+    pub show_cpu_band: bool,         // Min/max band behind the CPU line, toggled with 'b'
+    pub show_cpu_breakdown: bool,    // Stacked user/system/iowait/steal chart instead, toggled with 'B'
+    pub chart_span: usize,           // Index into CHART_SPANS_SECS, zoomed with '+'/'-'
+    pub chart_markers: Vec<f64>,     // Chart x positions marked with 'a'; dropped once out of history
+    pub temp_view: TempView,
+    pub show_process_detail: Option<u32>, // Detail overlay, pinned to the PID selected when it opened
+    pub show_help: bool,             // Keybinding overlay
+    pub show_diagnostics: bool,      // Frame/sample rate corner ('d')
+    pub diagnostics: Diagnostics,    // Fed by the event loop
+    pub show_stats: bool,            // --show-stats: frame rate and drain size in the footer
+    pub kill_menu: Option<KillMenu>, // Modal: takes all keys while open
+    pub show_sockets: bool,          // Connections table ('N')
+    pub socket_scroll: usize,        // Selected row in it
+    pub socket_rows: Cell<usize>,    // Body rows it showed on the last frame, written by the renderer
+    pub show_storage: bool,          // Storage table of every disk ('D')
+    pub storage_sort: DiskSortKey,
+    pub storage_scroll: usize,       // Selected row in it
+    pub storage_rows: Cell<usize>,   // Body rows it showed on the last frame, written by the renderer
+    pub focused_panel: FocusPanel,
+    pub commands: Option<Sender<MonitorCommand>>, // To the monitor thread
+
+    // Alerts
+    pub alerts: AlertEngine,
+    pub alert_bell: bool,   // Ring the terminal bell when a rule starts firing
+    pub bell_pending: bool, // Consumed by the event loop
+    pub notifier: Option<Notifier>, // Desktop notifications (--notify)
+    pub alert_log: Option<AlertLog>, // --alert-log
+
+    pub theme_index: usize, // Into theme::THEMES, cycled with 'c'
+    pub accent_index: Option<usize>, // Into theme::ACCENTS, cycled with 'T'; None keeps the theme's own
+    pub color_support: ColorSupport,
+    pub palette: Palette, // --palette: heatmap and usage gauge scale
+    pub heat_buckets: Option<[u8; 5]>,   // --heatmap-buckets: where that scale changes color
+    pub heat_colors: Option<[Color; 6]>, // --heatmap-colors: replaces the scale's colors
+    pub units: UnitMode, // --units si|binary
+    pub temp_unit: TempUnit, // --temp-unit, toggled with 'f'; samples stay in Celsius
+
+    // Footer
+    pub footer_message: Option<(String, Instant)>, // Transient warning + when it was raised
+    pub monitor_disconnected: bool, // Sampling thread is gone; everything on screen is stale
+    pub last_sample_at: Instant,    // Arrival of the latest sample (startup until the first)
+    pub watch_staleness: bool,      // Flag a silent monitor; off for --replay, which may pause or end
 }
 
 impl App {
-    pub fn new(max_history: usize) -> Self {
+    pub fn new(chart_history: usize) -> Self {
         Self {
             should_quit: false,
-            cpu_history_total: VecDeque::with_capacity(max_history),
-            ram_history: VecDeque::with_capacity(max_history),
-            net_rx_history: VecDeque::with_capacity(max_history),
-            net_tx_history: VecDeque::with_capacity(max_history),
-            temp_history: VecDeque::with_capacity(max_history),
+            confirm_quit: false,
+            pending_quit: None,
+            cpu_history_total: VecDeque::with_capacity(chart_history),
+            cpu_history_min: VecDeque::with_capacity(chart_history),
+            cpu_history_max: VecDeque::with_capacity(chart_history),
+            cpu_stack_history: std::array::from_fn(|_| VecDeque::with_capacity(chart_history)),
+            ram_history: VecDeque::with_capacity(chart_history),
+            net_rx_history: VecDeque::with_capacity(chart_history),
+            net_tx_history: VecDeque::with_capacity(chart_history),
+            temp_history: VecDeque::with_capacity(chart_history),
+            sensor_history: BTreeMap::new(),
+            cpu_summary: RunningStats::default(),
+            net_rx_summary: RunningStats::default(),
+            net_tx_summary: RunningStats::default(),
+            temp_summary: RunningStats::default(),
+            cpu_raw: VecDeque::with_capacity(chart_history),
+            ram_raw: VecDeque::with_capacity(chart_history),
+            net_rx_raw: VecDeque::with_capacity(chart_history),
+            net_tx_raw: VecDeque::with_capacity(chart_history),
+            smoothing_alpha: DEFAULT_SMOOTHING_ALPHA,
+            smoothing_enabled: false,
+            moving_average_window: DEFAULT_MOVING_AVERAGE,
+            moving_average_charts: ChartId::ALL.to_vec(),
+            moving_average_enabled: false,
+            chart_fill: false,
             cpu_core_history: Vec::new(), // Init dynamically
+            cpu_core_ids: Vec::new(),
+            core_types: HashMap::new(),
+            heatmap_columns: Cell::new(0),
             processes: Vec::new(),
+            all_processes: Arc::default(),
             disks: Vec::new(),
+            disk_io: Vec::new(),
+            disk_busy: Vec::new(),
+            disk_health: Vec::new(),
+            disk_scroll: 0,
+            disk_rows: Cell::new(0),
             temps: Vec::new(),
+            sockets: Vec::new(),
             last_stats: None,
-            max_history_len: max_history,
+            swap_samples: VecDeque::new(),
+            swap_rate: 0.0,
+            net_session_rx: 0,
+            net_session_tx: 0,
+            net_last_counters: None,
+            session_start: Instant::now(),
+            peak_cpu: 0.0,
+            peak_ram: 0.0,
+            process_cpu_history: HashMap::new(),
+            last_process_sample: None,
+            chart_history_len: chart_history,
+            heatmap_fixed_width: None,
             
             accumulated_stats: Vec::with_capacity(1000),
             last_chart_update: Instant::now(),
             chart_tick_count: 0.0,
 
             process_scroll_state: 0,
-            process_sort_by_cpu: true,
+            process_sort_key: ProcessSortKey::Cpu,
+            process_sort_ascending: false,
+            sort_locked: false,
+            process_tree_view: false,
+            process_tree: Vec::new(),
+            tree_scroll_state: 0,
+            process_grouped: false,
+            group_key: GroupKey::Name,
+            process_groups: Vec::new(),
+            group_scroll_state: 0,
+            user_filter: None,
+            hide_kernel: false,
+            pinned: HashSet::new(),
+            process_table_layout: RefCell::new(ProcessTableLayout::default()),
+            columns: DEFAULT_COLUMNS.to_vec(),
+            process_limit: None,
+            irix_mode: true,
+            display_mode: DisplayMode::Full,
+            page_scroll: 0,
+            show_cpu_band: true,
+            show_cpu_breakdown: false,
+            chart_span: DEFAULT_CHART_SPAN,
+            chart_markers: Vec::new(),
+            temp_view: TempView::Max,
+            show_process_detail: None,
+            show_help: false,
+            show_diagnostics: false,
+            diagnostics: Diagnostics::default(),
+            show_stats: false,
+            kill_menu: None,
+            show_sockets: false,
+            socket_scroll: 0,
+            socket_rows: Cell::new(0),
+            show_storage: false,
+            storage_sort: DiskSortKey::Usage,
+            storage_scroll: 0,
+            storage_rows: Cell::new(0),
+            focused_panel: FocusPanel::Processes,
+            commands: None,
+
+            alerts: AlertEngine::new(Vec::new()),
+            alert_bell: false,
+            bell_pending: false,
+            notifier: None,
+            alert_log: None,
+
+            theme_index: 0,
+            accent_index: None,
+            color_support: ColorSupport::TrueColor,
+            palette: Palette::Classic,
+            heat_buckets: None,
+            heat_colors: None,
+            units: UnitMode::Binary,
+            temp_unit: TempUnit::Celsius,
+
+            footer_message: None,
+            monitor_disconnected: false,
+            last_sample_at: Instant::now(),
+            watch_staleness: true,
+        }
+    }
+
+    pub fn set_footer_message(&mut self, msg: String) {
+        self.footer_message = Some((msg, Instant::now()));
+    }
+
+    // Footer messages stay up for a few seconds, then drop out of the layout.
+    pub fn active_footer_message(&self) -> Option<&str> {
+        if self.quit_pending() {
+            return Some("PRESS Q AGAIN TO QUIT");
+        }
+        match &self.footer_message {
+            Some((msg, since)) if since.elapsed().as_secs() < FOOTER_MESSAGE_SECS => Some(msg.as_str()),
+            _ => None,
         }
     }
 
     pub fn on_tick(&mut self, stats: SystemStats) {
+        self.on_tick_at(stats, Instant::now());
+    }
+
+    // `now` gates the chart updates, so tests can step the clock instead of sleeping
+    fn on_tick_at(&mut self, stats: SystemStats, now: Instant) {
+        self.last_sample_at = now;
+
         // 1. Snapshot Update
         self.disks = stats.disks.clone();
+        self.disk_io = stats.disk_io.clone();
+        self.disk_busy = stats.disk_busy.clone();
+        self.disk_health = stats.disk_health.clone();
         self.temps = stats.temperatures.clone();
+        self.sockets = stats.sockets.clone();
+        self.socket_scroll = self.socket_scroll.min(self.sockets.len().saturating_sub(1));
+        self.storage_scroll = self.storage_scroll.min(self.disks.len().saturating_sub(1));
+
+        // Session totals accumulate deltas so an interface counter reset
+        // (counter going backwards) contributes nothing instead of wrapping
+        if let Some((last_rx, last_tx)) = self.net_last_counters {
+            self.net_session_rx += stats.rx_bytes.saturating_sub(last_rx);
+            self.net_session_tx += stats.tx_bytes.saturating_sub(last_tx);
+        }
+        self.net_last_counters = Some((stats.rx_bytes, stats.tx_bytes));
+        self.peak_cpu = self.peak_cpu.max(stats.total_cpu_usage);
+        if stats.ram_total > 0 {
+            self.peak_ram = self.peak_ram.max(stats.ram_used as f64 / stats.ram_total as f64 * 100.0);
+        }
         
-        // Process Sorting & Selection
-        let mut procs = stats.processes.clone();
-        if self.process_sort_by_cpu {
-            procs.sort_by(|a, b| b.cpu.partial_cmp(&a.cpu).unwrap_or(std::cmp::Ordering::Equal));
-        } else {
-            procs.sort_by(|a, b| b.mem.cmp(&a.mem));
+        // Process Sorting & Selection, only when the monitor sent a fresh list
+        // (history covers every process, not just the filtered ones)
+        if let Some(procs) = &stats.processes {
+            if self.last_process_sample.is_none_or(|t| t.elapsed().as_secs_f64() >= PROCESS_HISTORY_INTERVAL_SECS) {
+                record_process_cpu(&mut self.process_cpu_history, procs);
+                self.last_process_sample = Some(Instant::now());
+            }
+            if self.all_processes.is_empty() {
+                self.check_process_access(procs);
+            }
+            self.all_processes = Arc::clone(procs);
+            self.load_processes(procs);
         }
-        self.processes = procs;
         self.last_stats = Some(stats.clone());
 
-        // 2. Heatmap Update (Every tick or throttled?)
-        // Let's update heatmap every tick for "flow" visual, or every chart update?
-        // Updating every 1ms is too fast for visual, let's do it with chart update (1s) or faster (e.g. 100ms)?
-        // For visual smoothness, 1s is choppy. Let's do it every ~100ms if possible.
-        // For now, let's sync with Chart Update (1s) to match the other graphs.
-        // OR better: Update accumulation logic.
+        let fired = self.alerts.evaluate(&stats, now);
+        if !fired.is_empty() && self.alert_bell {
+            self.bell_pending = true;
+        }
+        if let Some(notifier) = &mut self.notifier {
+            for alert in &fired {
+                notifier.notify(alert, self.temp_unit, now);
+            }
+        }
+        if let Some(log) = &mut self.alert_log {
+            let result = fired.iter().try_for_each(|a| log.fired(a)).and_then(|_| self.alerts.cleared().try_for_each(|a| log.cleared(&a)));
+            if let Err(err) = result {
+                self.alert_log = None;
+                self.set_footer_message(format!("ALERT LOG DISABLED: {}", err));
+            }
+        }
+
+        // 2. Charts and heatmap: one sample arrives per monitor fast tick; every sample
+        // since the last chart point is averaged into the next one
 
         self.accumulated_stats.push(stats);
 
-        if self.last_chart_update.elapsed().as_secs_f64() >= 0.1 { // 10 FPS updates for smoother visuals
+        if now.saturating_duration_since(self.last_chart_update).as_secs_f64() >= CHART_INTERVAL_SECS { // 10 FPS updates for smoother visuals
             self.update_charts();
-            self.last_chart_update = Instant::now();
+            self.last_chart_update = now;
+        }
+    }
+
+    // How long the monitor has been silent, once that's past STALE_AFTER. A thread
+    // that panicked shows up as a disconnect; one stuck in a syscall only as silence.
+    pub fn stale_for(&self, now: Instant) -> Option<Duration> {
+        let silent = now.saturating_duration_since(self.last_sample_at);
+        (self.watch_staleness && !self.monitor_disconnected && silent >= STALE_AFTER).then_some(silent)
+    }
+
+    // Without enough privileges much of the process table is blank and signals fail;
+    // say so once, on the first sample, rather than leave it to be discovered
+    fn check_process_access(&mut self, processes: &[ProcessInfo]) {
+        let restricted = processes.iter().filter(|p| p.is_restricted()).count();
+        if !processes.is_empty() && restricted as f64 / processes.len() as f64 >= RESTRICTED_WARN_SHARE {
+            self.set_footer_message(format!(
+                "{} OF {} PROCESSES NOT READABLE: RUN WITH MORE PRIVILEGES FOR FULL DETAILS AND SIGNALS",
+                restricted, processes.len()
+            ));
+        }
+    }
+
+    // Rows grouped by user carry the user in `process.name`; pins are process names
+    // and don't apply to them
+    pub fn grouped_by_user(&self) -> bool {
+        self.process_grouped && !self.process_tree_view && self.group_key == GroupKey::User
+    }
+
+    pub fn selected_group(&self) -> Option<&ProcessGroup> {
+        if self.process_grouped && !self.process_tree_view {
+            self.process_groups.get(self.group_scroll_state)
+        } else {
+            None
+        }
+    }
+
+    pub fn process_cpu_history(&self, pid: u32) -> Option<&VecDeque<f32>> {
+        self.process_cpu_history.get(&pid).map(|(_, cpu)| cpu)
+    }
+
+    // Selected process from whichever view is active. Tree rows carry subtree
+    // totals, so look the PID up in the flat snapshot to get its own figures.
+    // A group only resolves to a process when it has a single member.
+    pub fn selected_process(&self) -> Option<&ProcessInfo> {
+        if self.process_tree_view {
+            let pid = self.process_tree.get(self.tree_scroll_state)?.process.pid;
+            self.processes.iter().find(|p| p.pid == pid)
+        } else if self.process_grouped {
+            match self.selected_group()?.pids.as_slice() {
+                [pid] => self.processes.iter().find(|p| p.pid == *pid),
+                _ => None,
+            }
+        } else {
+            self.processes.get(self.process_scroll_state)
+        }
+    }
+
+    fn sort_processes(&self, procs: &mut [ProcessInfo]) {
+        let (key, ascending) = (self.process_sort_key, self.process_sort_ascending);
+        procs.sort_by(|a, b| {
+            let ord = key.compare(a, b);
+            if ascending { ord } else { ord.reverse() }
+        });
+        // Stable, so pinned rows keep the active order among themselves
+        procs.sort_by_key(|p| !self.pinned.contains(&p.name));
+    }
+
+    // Filter and sort a snapshot into the flat list, then rebuild the derived views
+    fn load_processes(&mut self, all: &[ProcessInfo]) {
+        let mut procs: Vec<ProcessInfo> = all.iter()
+            .filter(|p| self.user_filter.as_ref().is_none_or(|user| p.user.as_ref() == Some(user)))
+            .filter(|p| !(self.hide_kernel && p.is_kernel()))
+            .cloned()
+            .collect();
+        self.sort_processes(&mut procs);
+        if self.sort_locked {
+            // Known PIDs keep their rows; new ones follow in sorted order
+            let slots: HashMap<u32, usize> = self.processes.iter().enumerate().map(|(i, p)| (p.pid, i)).collect();
+            procs.sort_by_key(|p| slots.get(&p.pid).copied().unwrap_or(usize::MAX));
+        }
+        self.processes = procs;
+        self.rebuild_views();
+    }
+
+    // Filters changed: rebuild from the last sample and start at the top
+    fn reload_processes(&mut self) {
+        let all = Arc::clone(&self.all_processes);
+        self.load_processes(&all);
+        self.process_scroll_state = 0;
+        self.tree_scroll_state = 0;
+        self.group_scroll_state = 0;
+    }
+
+    fn reorder(&mut self) {
+        let mut procs = std::mem::take(&mut self.processes);
+        self.sort_processes(&mut procs);
+        self.processes = procs;
+        self.rebuild_views();
+    }
+
+    fn resort(&mut self) {
+        self.reorder();
+        self.process_scroll_state = 0;
+        self.tree_scroll_state = 0;
+        self.group_scroll_state = 0;
+    }
+
+    // Tree and group rows are derived from the sorted flat list; only the active one is kept current
+    fn rebuild_views(&mut self) {
+        if self.process_tree_view {
+            self.process_tree = build_process_tree(&self.processes, self.process_sort_key, self.process_sort_ascending);
+        }
+        if self.process_grouped {
+            self.process_groups = build_process_groups(&self.processes, self.group_key, self.process_sort_key, self.process_sort_ascending);
+            if self.group_key == GroupKey::Name {
+                self.process_groups.sort_by_key(|g| !self.pinned.contains(&g.process.name));
+            }
+        }
+    }
+
+    fn effective_alpha(&self) -> f64 {
+        if self.smoothing_enabled { self.smoothing_alpha } else { 1.0 }
+    }
+
+    // Recompute the displayed series from the raw ones after the smoothing setting changes
+    pub fn toggle_smoothing(&mut self) {
+        self.smoothing_enabled = !self.smoothing_enabled;
+        self.recompute_smoothed();
+    }
+
+    fn recompute_smoothed(&mut self) {
+        let alpha = self.effective_alpha();
+        self.cpu_history_total = ema(&self.cpu_raw, alpha);
+        self.ram_history = ema(&self.ram_raw, alpha);
+        self.net_rx_history = ema(&self.net_rx_raw, alpha);
+        self.net_tx_history = ema(&self.net_tx_raw, alpha);
+    }
+
+    pub fn save_state(&self, path: &Path) -> anyhow::Result<()> {
+        HistorySnapshot {
+            version: HISTORY_VERSION,
+            chart_tick_count: self.chart_tick_count,
+            cpu: self.cpu_raw.clone(),
+            cpu_min: self.cpu_history_min.clone(),
+            cpu_max: self.cpu_history_max.clone(),
+            ram: self.ram_raw.clone(),
+            net_rx: self.net_rx_raw.clone(),
+            net_tx: self.net_tx_raw.clone(),
+            temp: self.temp_history.clone(),
+            cpu_stack: self.cpu_stack_history.clone(),
+        }.save(path)
+    }
+
+    // Ok(false) when there was nothing usable to restore (missing or incompatible file)
+    pub fn load_state(&mut self, path: &Path) -> anyhow::Result<bool> {
+        let Some(saved) = HistorySnapshot::load(path)? else { return Ok(false) };
+        let max = self.chart_history_len;
+        let trim = |mut series: VecDeque<(f64, f64)>| {
+            while series.len() > max {
+                series.pop_front();
+            }
+            series
+        };
+        self.chart_tick_count = saved.chart_tick_count;
+        self.cpu_raw = trim(saved.cpu);
+        self.cpu_history_min = trim(saved.cpu_min);
+        self.cpu_history_max = trim(saved.cpu_max);
+        self.ram_raw = trim(saved.ram);
+        self.net_rx_raw = trim(saved.net_rx);
+        self.net_tx_raw = trim(saved.net_tx);
+        self.temp_history = trim(saved.temp);
+        self.cpu_stack_history = saved.cpu_stack.map(trim);
+        self.recompute_smoothed();
+        Ok(true)
+    }
+
+    // One heatmap sample per rendered column, so cells line up with the terminal grid,
+    // unless --heatmap-width fixed the count (the canvas then scales the cells)
+    pub fn heatmap_width(&self) -> usize {
+        if let Some(width) = self.heatmap_fixed_width {
+            return width;
+        }
+        match self.heatmap_columns.get() {
+            0 => HEATMAP_WIDTH,
+            cols => cols,
+        }
+    }
+
+    // Swap usage % while swap is both mostly full and still growing
+    pub fn swap_pressure(&self) -> Option<f64> {
+        let stats = self.last_stats.as_ref().filter(|s| s.swap_total > 0)?;
+        let pct = stats.swap_used as f64 / stats.swap_total as f64 * 100.0;
+        (pct >= SWAP_PRESSURE_PCT && self.swap_rate >= SWAP_PRESSURE_RATE).then_some(pct)
+    }
+
+    // Active theme, already reduced to the terminal's color support
+    pub fn theme(&self) -> Theme {
+        theme::THEMES[self.theme_index % theme::THEMES.len()]
+            .with_accent(self.accent_index.and_then(|i| theme::ACCENTS.get(i)))
+            .with_palette(self.palette)
+            .with_heat_scale(self.heat_buckets, self.heat_colors)
+            .degrade(self.color_support)
+    }
+
+    fn quit_pending(&self) -> bool {
+        self.pending_quit.is_some_and(|t| t.elapsed().as_secs() < QUIT_CONFIRM_SECS)
+    }
+
+    fn request_quit(&mut self) {
+        if !self.confirm_quit || self.quit_pending() {
+            self.should_quit = true;
+        } else {
+            self.pending_quit = Some(Instant::now());
         }
     }
 
@@ -99,100 +901,1564 @@ impl App {
         let avg_cpu: f32 = self.accumulated_stats.iter().map(|s| s.total_cpu_usage).sum::<f32>() / count;
         
         // --- Heatmap Logic ---
-        // We need average per core.
-        // Assuming all stats have same number of cores.
-        if let Some(first) = self.accumulated_stats.first() {
-            let core_count = first.cpu_usage.len();
-            if self.cpu_core_history.len() != core_count {
-                self.cpu_core_history = vec![VecDeque::with_capacity(100); core_count]; // 100 cols wide
-            }
-
-            for i in 0..core_count {
-                let core_sum: f32 = self.accumulated_stats.iter().map(|s| s.cpu_usage.get(i).cloned().unwrap_or(0.0)).sum();
-                let core_avg = core_sum / count;
-                
-                if self.cpu_core_history[i].len() >= 100 { // Heatmap width
-                    self.cpu_core_history[i].pop_front();
+        // Core set follows the newest sample that reported any cores. Samples with an
+        // empty `cpu_usage` (warm-up) are ignored rather than recorded as zero load.
+        let reporting: Vec<&SystemStats> = self.accumulated_stats.iter().filter(|s| !s.cpu_usage.is_empty()).collect();
+        if let Some(latest) = reporting.last() {
+            if self.cpu_core_ids != latest.cpu_ids {
+                realign_core_history(&mut self.cpu_core_history, &mut self.cpu_core_ids, &latest.cpu_ids);
+            }
+            let width = self.heatmap_width();
+
+            for (&id, history) in self.cpu_core_ids.iter().zip(self.cpu_core_history.iter_mut()) {
+                // Only average over samples that actually include this core
+                let (sum, n) = reporting.iter()
+                    .filter_map(|s| s.cpu_ids.iter().position(|&c| c == id).and_then(|i| s.cpu_usage.get(i)))
+                    .fold((0.0_f32, 0u32), |(sum, n), v| (sum + v, n + 1));
+                let core_avg = if n > 0 { sum / n as f32 } else { 0.0 };
+
+                // Oldest columns fall off the left; after a shrink this drops several at once
+                while history.len() >= width {
+                    history.pop_front();
+                }
+                if history.capacity() > width * 2 {
+                    history.shrink_to(width);
                 }
-                self.cpu_core_history[i].push_back(core_avg as u8);
+                history.push_back(core_avg.clamp(0.0, 100.0) as u8);
             }
         }
 
-        // Global Charts
-        if self.cpu_history_total.len() >= self.max_history_len { self.cpu_history_total.pop_front(); }
-        self.cpu_history_total.push_back((self.chart_tick_count, avg_cpu as f64));
+        // Global Charts (raw average is kept, the displayed series is EMA-smoothed)
+        let alpha = self.effective_alpha();
+        let (x, max) = (self.chart_tick_count, self.chart_history_len);
+        push_smoothed(&mut self.cpu_raw, &mut self.cpu_history_total, (x, avg_cpu as f64), alpha, max);
+        if let Some(&(oldest, _)) = self.cpu_history_total.front() {
+            self.chart_markers.retain(|m| *m >= oldest);
+        }
+        self.cpu_summary.push(avg_cpu as f64);
+        let (min_cpu, max_cpu) = self.accumulated_stats.iter()
+            .fold((f32::MAX, f32::MIN), |(lo, hi), s| (lo.min(s.total_cpu_usage), hi.max(s.total_cpu_usage)));
+        for (series, value) in [(&mut self.cpu_history_min, min_cpu), (&mut self.cpu_history_max, max_cpu)] {
+            if series.len() >= max { series.pop_front(); }
+            series.push_back((x, value as f64));
+        }
+        // Each stacked series is the top edge of its band; nothing is recorded without the data
+        let breakdowns: Vec<CpuBreakdown> = self.accumulated_stats.iter().filter_map(|s| s.cpu_breakdown).collect();
+        if !breakdowns.is_empty() {
+            let avg = |part: fn(&CpuBreakdown) -> f32| breakdowns.iter().map(part).sum::<f32>() as f64 / breakdowns.len() as f64;
+            let parts = [avg(|b| b.user), avg(|b| b.system), avg(|b| b.iowait), avg(|b| b.steal)];
+            let mut top = 0.0;
+            for (series, part) in self.cpu_stack_history.iter_mut().zip(parts) {
+                top += part;
+                if series.len() >= max { series.pop_front(); }
+                series.push_back((x, top));
+            }
+        }
         
         // RAM
         let avg_ram: f64 = self.accumulated_stats.iter().map(|s| s.ram_used as f64).sum::<f64>() / count as f64;
         let total = self.accumulated_stats[0].ram_total as f64;
-        if self.ram_history.len() >= self.max_history_len { self.ram_history.pop_front(); }
-        self.ram_history.push_back((self.chart_tick_count, (avg_ram / total) * 100.0));
+        let ram_pct = if total > 0.0 { avg_ram / total * 100.0 } else { 0.0 }; // Replays and odd platforms can report no RAM
+        push_smoothed(&mut self.ram_raw, &mut self.ram_history, (x, ram_pct), alpha, max);
 
         // Net
         let avg_rx: f64 = self.accumulated_stats.iter().map(|s| s.rx_speed as f64).sum::<f64>() / count as f64;
         let avg_tx: f64 = self.accumulated_stats.iter().map(|s| s.tx_speed as f64).sum::<f64>() / count as f64;
-        if self.net_rx_history.len() >= self.max_history_len { self.net_rx_history.pop_front(); self.net_tx_history.pop_front(); }
-        self.net_rx_history.push_back((self.chart_tick_count, avg_rx));
-        self.net_tx_history.push_back((self.chart_tick_count, avg_tx));
+        push_smoothed(&mut self.net_rx_raw, &mut self.net_rx_history, (x, avg_rx), alpha, max);
+        push_smoothed(&mut self.net_tx_raw, &mut self.net_tx_history, (x, avg_tx), alpha, max);
+        self.net_rx_summary.push(avg_rx);
+        self.net_tx_summary.push(avg_tx);
 
-        // Temp (Max observed in this interval)
+        // Swap growth over the last few seconds (a single window is too short to be meaningful)
+        if let Some(last) = self.accumulated_stats.last() {
+            self.swap_samples.push_back((last.timestamp, last.swap_used));
+            while self.swap_samples.front().is_some_and(|(t, _)| last.timestamp.duration_since(*t).as_secs_f64() > SWAP_RATE_WINDOW_SECS) {
+                self.swap_samples.pop_front();
+            }
+            if let (Some(&(t0, s0)), Some(&(t1, s1))) = (self.swap_samples.front(), self.swap_samples.back()) {
+                let dt = t1.duration_since(t0).as_secs_f64();
+                self.swap_rate = if dt > 0.0 { (s1 as f64 - s0 as f64) / dt } else { 0.0 };
+            }
+        }
+
+        // Temp (Max observed in this interval; nothing recorded without sensors)
         let max_temp = self.accumulated_stats.iter()
-            .flat_map(|s| s.temperatures.iter().map(|(_, t)| *t))
-            .fold(0.0_f32, f32::max);
-            
-        if self.temp_history.len() >= self.max_history_len { self.temp_history.pop_front(); }
-        self.temp_history.push_back((self.chart_tick_count, max_temp as f64));
+            .flat_map(|s| s.temperatures.iter().map(|t| t.celsius))
+            .reduce(f32::max);
+        if let Some(max_temp) = max_temp {
+            if self.temp_history.len() >= self.chart_history_len { self.temp_history.pop_front(); }
+            self.temp_history.push_back((self.chart_tick_count, max_temp as f64));
+            self.temp_summary.push(max_temp as f64);
+        }
+        let mut sensor_max: BTreeMap<&str, f32> = BTreeMap::new();
+        for t in self.accumulated_stats.iter().flat_map(|s| &s.temperatures) {
+            let entry = sensor_max.entry(t.name.as_str()).or_insert(t.celsius);
+            *entry = entry.max(t.celsius);
+        }
+        for (name, t) in sensor_max {
+            let series = self.sensor_history.entry(name.to_string()).or_default();
+            if series.len() >= self.chart_history_len { series.pop_front(); }
+            series.push_back((self.chart_tick_count, t as f64));
+        }
 
         self.accumulated_stats.clear();
     }
 
-    pub fn on_key(&mut self, c: char) {
-        match c {
-            'q' | 'Q' => self.should_quit = true,
-            'j' | 'J' => { // Down
-                if !self.processes.is_empty() {
-                    self.process_scroll_state = (self.process_scroll_state + 1).min(self.processes.len() - 1);
-                }
+    pub fn on_key(&mut self, key: KeyEvent) {
+        if self.kill_menu.is_some() {
+            self.on_kill_menu_key(key.code);
+            return;
+        }
+        if self.show_sockets || self.show_storage {
+            self.on_table_key(key);
+            return;
+        }
+        if let Some(action) = keys::action_for(key.code, key.modifiers) {
+            self.perform(action);
+        }
+    }
+
+    // The connections and storage tables are modal: they keep the focus while open, and
+    // keys that would act on the process list hidden behind them do nothing
+    fn on_table_key(&mut self, key: KeyEvent) {
+        let own_key = if self.show_sockets { KeyCode::Char('N') } else { KeyCode::Char('D') };
+        if matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) || key.code == own_key {
+            // Closes the table rather than quitting
+            if self.show_sockets { self.toggle_sockets() } else { self.toggle_storage() }
+            return;
+        }
+        let Some(action) = keys::action_for(key.code, key.modifiers) else { return };
+        let allowed = match action {
+            Action::Down | Action::Up | Action::Top | Action::Bottom
+            | Action::PageDown | Action::PageUp | Action::HalfPageDown | Action::HalfPageUp => true,
+            Action::CycleSort => self.show_storage,
+            Action::ToggleSockets | Action::ToggleStorage | Action::ToggleHelp | Action::ToggleDiagnostics => true,
+            _ => false,
+        };
+        if allowed {
+            self.perform(action);
+        }
+    }
+
+    fn on_kill_menu_key(&mut self, code: KeyCode) {
+        let Some(menu) = &mut self.kill_menu else { return };
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => menu.selected = (menu.selected + 1).min(KillSignal::ALL.len() - 1),
+            KeyCode::Up | KeyCode::Char('k') => menu.selected = menu.selected.saturating_sub(1),
+            KeyCode::Enter => {
+                let (pid, signal) = (menu.pid, KillSignal::ALL[menu.selected]);
+                self.kill_menu = None;
+                self.send_signal(pid, signal);
             }
-            'k' | 'K' => { // Up (or Kill? Let's use K for up and x for kill to be safer, or just K for up context)
-                // Vim style navigation
-                if self.process_scroll_state > 0 {
-                    self.process_scroll_state -= 1;
+            KeyCode::Esc | KeyCode::Char('q') => self.kill_menu = None,
+            _ => {}
+        }
+    }
+
+    // Same key again returns to the flat list; the other key switches grouping over
+    fn toggle_grouping(&mut self, key: GroupKey) {
+        self.process_grouped = !(self.process_grouped && self.group_key == key);
+        self.group_key = key;
+        self.group_scroll_state = 0;
+        self.process_tree_view = false;
+        self.rebuild_views();
+    }
+
+    // The monitor only walks the fd tables while the table is on screen
+    fn toggle_sockets(&mut self) {
+        if self.show_storage {
+            self.toggle_storage();
+        }
+        self.show_sockets = !self.show_sockets;
+        self.socket_scroll = 0;
+        self.focused_panel = if self.show_sockets { FocusPanel::Connections } else { FocusPanel::Processes };
+        if let Some(tx) = &self.commands {
+            let _ = tx.send(MonitorCommand::WatchSockets(self.show_sockets));
+        }
+    }
+
+    // Both tables use the same popup area, so opening one closes the other
+    fn toggle_storage(&mut self) {
+        if self.show_sockets {
+            self.toggle_sockets();
+        }
+        self.show_storage = !self.show_storage;
+        self.storage_scroll = 0;
+        self.focused_panel = if self.show_storage { FocusPanel::Storage } else { FocusPanel::Processes };
+    }
+
+    // Disks in storage table order
+    pub fn storage_disks(&self) -> Vec<&DiskInfo> {
+        let mut disks: Vec<&DiskInfo> = self.disks.iter().collect();
+        match self.storage_sort {
+            DiskSortKey::Usage => disks.sort_by(|a, b| b.usage_pct().unwrap_or(0.0).total_cmp(&a.usage_pct().unwrap_or(0.0))),
+            DiskSortKey::Capacity => disks.sort_by_key(|d| std::cmp::Reverse(d.total)),
+            DiskSortKey::Mount => disks.sort_by(|a, b| a.mount.cmp(&b.mount)),
+        }
+        disks
+    }
+
+    // The monitor thread reports the outcome (including permission failures) in the footer
+    fn send_signal(&mut self, pid: u32, signal: KillSignal) {
+        let sent = self.commands.as_ref().is_some_and(|tx| tx.send(MonitorCommand::Kill { pid, signal }).is_ok());
+        if !sent {
+            self.set_footer_message("MONITOR IS NOT RUNNING, NOTHING SENT".to_string());
+        }
+    }
+
+    // Processes <-> disks. The connections and storage tables hold the focus while
+    // they're open, so Tab never reaches them.
+    fn cycle_focus(&mut self) {
+        self.focused_panel = match self.focused_panel {
+            FocusPanel::Processes if !self.disks.is_empty() => FocusPanel::Disks,
+            _ => FocusPanel::Processes,
+        };
+    }
+
+    // Length of, and cursor into, the focused list (for processes, whichever view is
+    // active). The disk list scrolls by its first row, so it ends a page early.
+    fn selection(&mut self) -> (usize, &mut usize) {
+        match self.focused_panel {
+            FocusPanel::Disks => return (self.disks.len().saturating_sub(self.disk_rows.get().max(1)) + 1, &mut self.disk_scroll),
+            FocusPanel::Connections => return (self.sockets.len(), &mut self.socket_scroll),
+            FocusPanel::Storage => return (self.disks.len(), &mut self.storage_scroll),
+            FocusPanel::Processes => {}
+        }
+        if self.process_tree_view {
+            (self.process_tree.len(), &mut self.tree_scroll_state)
+        } else if self.process_grouped {
+            (self.process_groups.len(), &mut self.group_scroll_state)
+        } else {
+            (self.listed_processes(), &mut self.process_scroll_state)
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let (len, index) = self.selection();
+        *index = index.saturating_add_signed(delta).min(len.saturating_sub(1));
+    }
+
+    // Body rows the focused list showed on the last frame
+    fn visible_rows(&self) -> usize {
+        match self.focused_panel {
+            FocusPanel::Disks => self.disk_rows.get().max(1),
+            FocusPanel::Connections => self.socket_rows.get().max(1),
+            FocusPanel::Storage => self.storage_rows.get().max(1),
+            FocusPanel::Processes => {
+                let height = self.process_table_layout.borrow().area.height;
+                height.saturating_sub(PROCESS_TABLE_HEADER_ROWS).max(1) as usize
+            }
+        }
+    }
+
+    // Only reached when mouse capture is on; keyboard handling is unaffected
+    pub fn on_mouse(&mut self, event: MouseEvent) {
+        if self.show_help || self.show_process_detail.is_some() || self.kill_menu.is_some() || self.show_sockets || self.show_storage {
+            return;
+        }
+        match event.kind {
+            MouseEventKind::ScrollDown => self.perform(Action::Down),
+            MouseEventKind::ScrollUp => self.perform(Action::Up),
+            MouseEventKind::Down(MouseButton::Left) => self.click_process_table(event.column, event.row),
+            _ => {}
+        }
+    }
+
+    fn click_process_table(&mut self, x: u16, y: u16) {
+        let layout = self.process_table_layout.borrow().clone();
+        let area = layout.area;
+        if x < area.x || x >= area.right() || y < area.y || y >= area.bottom() {
+            return;
+        }
+
+        if y == area.y {
+            // Clicking the active column flips direction, any other column sorts by it
+            if let Some(&(key, _)) = layout.columns.iter().find(|(_, r)| x >= r.x && x < r.right()) {
+                if key == self.process_sort_key {
+                    self.process_sort_ascending = !self.process_sort_ascending;
+                } else {
+                    self.process_sort_key = key;
+                    self.process_sort_ascending = key.default_ascending();
                 }
+                self.resort();
             }
-            's' | 'S' => { // Sort Toggle
-                self.process_sort_by_cpu = !self.process_sort_by_cpu;
-                self.process_scroll_state = 0; // Reset scroll
+            return;
+        }
+
+        let Some(row) = (y - area.y).checked_sub(PROCESS_TABLE_HEADER_ROWS) else { return };
+        // Clicks on the blank space below the last row select nothing
+        let index = layout.offset + row as usize;
+        if index >= layout.rows {
+            return;
+        }
+        if self.process_tree_view {
+            if index < self.process_tree.len() {
+                self.tree_scroll_state = index;
             }
-            'x' | 'X' => { // Kill Process
-                // Real kill logic would go here. For safety in demo, we just print or log.
-                // In real app: sys.process(pid).kill();
+        } else if self.process_grouped {
+            if index < self.process_groups.len() {
+                self.group_scroll_state = index;
             }
-            _ => {}
+        } else if index < self.listed_processes() {
+            self.process_scroll_state = index;
         }
     }
-    
-    // Special handling for arrow keys if they were passed as chars (not happening in main.rs currently)
-    // We need to update main.rs to pass KeyCode enum or handle arrows there.
-    pub fn on_key_code(&mut self, code: crossterm::event::KeyCode) {
-        use crossterm::event::KeyCode;
-        match code {
-            KeyCode::Char('q') | KeyCode::Char('Q') => self.should_quit = true,
-            KeyCode::Down | KeyCode::Char('j') => {
-                if !self.processes.is_empty() {
-                    self.process_scroll_state = (self.process_scroll_state + 1).min(self.processes.len().saturating_sub(1));
+
+    // Rows in the flat list: every process unless --top capped it
+    pub fn listed_processes(&self) -> usize {
+        self.process_limit.map_or(self.processes.len(), |n| self.processes.len().min(n))
+    }
+
+    // "1h02m" / "4m10s" since launch
+    pub fn session_duration_label(&self) -> String {
+        let secs = self.session_start.elapsed().as_secs();
+        if secs >= 3600 {
+            format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60)
+        } else {
+            format!("{}m{:02}s", secs / 60, secs % 60)
+        }
+    }
+
+    pub fn chart_span_secs(&self) -> u64 {
+        CHART_SPANS_SECS[self.chart_span]
+    }
+
+    // sysinfo reports per-core (Irix) percentages; Solaris mode divides by the core count
+    pub fn process_cpu(&self, cpu: f32) -> f32 {
+        if self.irix_mode {
+            return cpu;
+        }
+        let cores = self.last_stats.as_ref().map_or(1, |s| s.cpu_usage.len().max(1));
+        cpu / cores as f32
+    }
+
+    // Human-readable visible span for chart titles ("30s", "5m")
+    pub fn chart_span_label(&self) -> String {
+        match self.chart_span_secs() {
+            s if s >= 60 && s.is_multiple_of(60) => format!("{}m", s / 60),
+            s => format!("{}s", s),
+        }
+    }
+
+    // Trailing points of a history series that fall inside the visible span
+    pub fn chart_window<'a>(&self, data: &'a VecDeque<(f64, f64)>) -> std::collections::vec_deque::Iter<'a, (f64, f64)> {
+        let points = (self.chart_span_secs() as f64 / CHART_INTERVAL_SECS) as usize;
+        data.range(data.len().saturating_sub(points)..)
+    }
+
+    // Window size when `chart` is currently drawn with a moving average
+    pub fn moving_average_for(&self, chart: ChartId) -> Option<usize> {
+        (self.moving_average_enabled && self.moving_average_window > 1 && self.moving_average_charts.contains(&chart))
+            .then_some(self.moving_average_window)
+    }
+
+    // The points of `data` to draw for `chart`: the visible span, moving-averaged when
+    // enabled. Points just before the span feed the first averages so the line doesn't
+    // start with a partial window.
+    pub fn chart_points(&self, chart: ChartId, data: &VecDeque<(f64, f64)>) -> Vec<(f64, f64)> {
+        let Some(window) = self.moving_average_for(chart) else {
+            return self.chart_window(data).cloned().collect();
+        };
+        let start = data.len() - self.chart_window(data).len();
+        let from = start.saturating_sub(window - 1);
+        let points: Vec<(f64, f64)> = data.range(from..).cloned().collect();
+        moving_average(&points, window).split_off(start - from)
+    }
+
+    // Max -> each sensor in reported order -> hottest overlay -> Max
+    fn cycle_temp_view(&mut self) {
+        let names: Vec<&String> = self.temps.iter().map(|t| &t.name).collect();
+        self.temp_view = match &self.temp_view {
+            _ if names.is_empty() => TempView::Max,
+            TempView::Max => TempView::Sensor(names[0].clone()),
+            TempView::Sensor(current) => match names.iter().position(|n| *n == current) {
+                Some(i) if i + 1 < names.len() => TempView::Sensor(names[i + 1].clone()),
+                _ => TempView::Hottest,
+            },
+            TempView::Hottest => TempView::Max,
+        };
+    }
+
+    // Sensor labels by latest reading, hottest first
+    pub fn hottest_sensors(&self, n: usize) -> Vec<&str> {
+        let mut temps: Vec<&TempInfo> = self.temps.iter().collect();
+        temps.sort_by(|a, b| b.celsius.total_cmp(&a.celsius));
+        temps.into_iter().take(n).map(|t| t.name.as_str()).collect()
+    }
+
+    fn perform(&mut self, action: Action) {
+        match action {
+            Action::Quit => self.request_quit(),
+            Action::Down => self.move_selection(1),
+            Action::Up => self.move_selection(-1),
+            Action::Top => *self.selection().1 = 0,
+            Action::Bottom => {
+                let (len, index) = self.selection();
+                *index = len.saturating_sub(1);
+            }
+            Action::PageDown => self.move_selection(self.visible_rows() as isize),
+            Action::PageUp => self.move_selection(-(self.visible_rows() as isize)),
+            Action::HalfPageDown => self.move_selection((self.visible_rows() / 2).max(1) as isize),
+            Action::ScrollPageDown => self.page_scroll = (self.page_scroll + 1).min(STACKED_SECTIONS - 1),
+            Action::ScrollPageUp => self.page_scroll = self.page_scroll.saturating_sub(1),
+            Action::HalfPageUp => self.move_selection(-((self.visible_rows() / 2).max(1) as isize)),
+            Action::ToggleSmoothing => self.toggle_smoothing(),
+            Action::ToggleMovingAverage => self.moving_average_enabled = !self.moving_average_enabled,
+            Action::ToggleChartFill => self.chart_fill = !self.chart_fill,
+            Action::ToggleTempUnit => {
+                self.temp_unit = self.temp_unit.toggle();
+                self.set_footer_message(format!("TEMPERATURES IN {}", self.temp_unit.suffix()));
+            }
+            Action::ScrollDisksDown => {
+                let last = self.disks.len().saturating_sub(self.disk_rows.get().max(1));
+                self.disk_scroll = (self.disk_scroll + 1).min(last);
+            }
+            Action::ScrollDisksUp => self.disk_scroll = self.disk_scroll.saturating_sub(1),
+            Action::ResetSummaries => {
+                for summary in [&mut self.cpu_summary, &mut self.net_rx_summary, &mut self.net_tx_summary, &mut self.temp_summary] {
+                    *summary = RunningStats::default();
                 }
             }
-            KeyCode::Up | KeyCode::Char('k') => {
-                if self.process_scroll_state > 0 {
-                    self.process_scroll_state -= 1;
+            Action::ToggleIrixMode => self.irix_mode = !self.irix_mode,
+            Action::ToggleSortLock => {
+                self.sort_locked = !self.sort_locked;
+                if !self.sort_locked {
+                    self.reorder(); // Rows jump back to live order; the selection stays put
                 }
             }
-            KeyCode::Char('s') => {
-                self.process_sort_by_cpu = !self.process_sort_by_cpu;
-                self.process_scroll_state = 0;
+            Action::ToggleCpuBand => self.show_cpu_band = !self.show_cpu_band,
+            Action::ToggleCpuBreakdown => {
+                self.show_cpu_breakdown = !self.show_cpu_breakdown;
+                if self.show_cpu_breakdown && self.cpu_stack_history[0].is_empty() {
+                    self.set_footer_message("NO CPU TIME BREAKDOWN ON THIS SYSTEM (YET)".to_string());
+                }
+            }
+            Action::CycleTempView => self.cycle_temp_view(),
+            Action::AddMarker => {
+                self.chart_markers.push(self.chart_tick_count);
+                self.set_footer_message(format!("MARKER {} SET", self.chart_markers.len()));
+            }
+            Action::ClearMarkers => self.chart_markers.clear(),
+            Action::ZoomIn => self.chart_span = self.chart_span.saturating_sub(1),
+            Action::ZoomOut => self.chart_span = (self.chart_span + 1).min(CHART_SPANS_SECS.len() - 1),
+            Action::ToggleCompact => {
+                self.display_mode = match self.display_mode {
+                    DisplayMode::Full => DisplayMode::Compact,
+                    DisplayMode::Compact => DisplayMode::Full,
+                };
+            }
+            Action::CycleTheme => self.theme_index = (self.theme_index + 1) % theme::THEMES.len(),
+            Action::CycleAccent => {
+                // Theme's own -> each preset -> theme's own
+                self.accent_index = match self.accent_index {
+                    None => Some(0),
+                    Some(i) if i + 1 < theme::ACCENTS.len() => Some(i + 1),
+                    Some(_) => None,
+                };
+                let name = self.accent_index.map_or("THEME DEFAULT", |i| theme::ACCENTS[i].name);
+                self.set_footer_message(format!("ACCENT: {}", name.to_uppercase()));
+            }
+            Action::ToggleDetail => {
+                // A group row pins its first member; the overlay then shows the group containing it
+                let pid = self.selected_process().map(|p| p.pid).or_else(|| self.selected_group().and_then(|g| g.pids.first().copied()));
+                self.show_process_detail = match (self.show_process_detail, pid) {
+                    (Some(_), _) => None,
+                    (None, Some(pid)) => Some(pid),
+                    (None, None) => {
+                        self.set_footer_message("NO PROCESS SELECTED".to_string());
+                        None
+                    }
+                };
+            }
+            Action::ExportReport => {
+                let msg = match report::write(self) {
+                    Ok(path) => format!("REPORT SAVED: {}", path.display()),
+                    Err(err) => format!("REPORT FAILED: {:#}", err),
+                };
+                self.set_footer_message(msg);
+            }
+            Action::TogglePin => {
+                if self.grouped_by_user() {
+                    self.set_footer_message("PINS ARE BY PROCESS NAME: LEAVE THE USER VIEW ('u') TO PIN".to_string());
+                    return;
+                }
+                let Some(name) = self.selected_process().or(self.selected_group().map(|g| &g.process)).map(|p| p.name.clone()) else {
+                    self.set_footer_message("NO PROCESS SELECTED".to_string());
+                    return;
+                };
+                let msg = if self.pinned.remove(&name) {
+                    format!("UNPINNED {}", name)
+                } else {
+                    self.pinned.insert(name.clone());
+                    format!("PINNED {}", name)
+                };
+                self.set_footer_message(msg);
+                self.reorder();
+            }
+            Action::OpenKillMenu => match self.selected_process() {
+                Some(p) => self.kill_menu = Some(KillMenu { pid: p.pid, name: p.name.clone(), selected: 0 }),
+                None => self.set_footer_message("SELECT A SINGLE PROCESS TO SIGNAL".to_string()),
+            },
+            Action::SuspendProcess | Action::ResumeProcess => match self.selected_process() {
+                Some(p) => {
+                    let signal = if action == Action::SuspendProcess { KillSignal::Stop } else { KillSignal::Cont };
+                    self.send_signal(p.pid, signal);
+                }
+                None => self.set_footer_message("SELECT A SINGLE PROCESS TO SIGNAL".to_string()),
+            },
+            Action::ToggleSockets => self.toggle_sockets(),
+            Action::ToggleStorage => self.toggle_storage(),
+            Action::CycleFocus => self.cycle_focus(),
+            Action::ToggleHelp => self.show_help = !self.show_help,
+            Action::ToggleDiagnostics => self.show_diagnostics = !self.show_diagnostics,
+            Action::CloseOverlay => {
+                self.show_help = false;
+                self.show_process_detail = None;
+            }
+            Action::ToggleTree => {
+                self.process_tree_view = !self.process_tree_view;
+                self.process_grouped = false;
+                self.rebuild_views();
+            }
+            Action::FilterByOwner => {
+                self.user_filter = match self.user_filter {
+                    Some(_) => None,
+                    None => self.selected_process().or(self.selected_group().map(|g| &g.process)).and_then(|p| p.user.clone()),
+                };
+                self.reload_processes();
+            }
+            Action::ToggleKernel => {
+                self.hide_kernel = !self.hide_kernel;
+                self.reload_processes();
+            }
+            Action::ToggleGroup => self.toggle_grouping(GroupKey::Name),
+            Action::ToggleUserGroup => self.toggle_grouping(GroupKey::User),
+            Action::CycleSort if self.focused_panel == FocusPanel::Storage => {
+                self.storage_sort = self.storage_sort.next();
+                self.storage_scroll = 0;
+            }
+            Action::CycleSort => {
+                self.process_sort_key = self.process_sort_key.next();
+                self.process_sort_ascending = self.process_sort_key.default_ascending();
+                self.resort();
+            }
+            Action::ReverseSort => {
+                self.process_sort_ascending = !self.process_sort_ascending;
+                self.resort();
             }
-            _ => {}
         }
     }
-}
\ No newline at end of file
+}
+
+// Rows follow the cores' stable ids: surviving cores keep their history even when
+// others go offline in between, and newly appeared cores are back-filled with idle
+// samples so every row stays aligned on the same time axis.
+fn realign_core_history(history: &mut Vec<VecDeque<u8>>, ids: &mut Vec<usize>, new_ids: &[usize]) {
+    let width = history.iter().map(|h| h.len()).max().unwrap_or(0);
+    let mut previous: HashMap<usize, VecDeque<u8>> = ids.drain(..).zip(history.drain(..)).collect();
+    for &id in new_ids {
+        let row = previous.remove(&id).unwrap_or_else(|| {
+            let mut blank = VecDeque::with_capacity(width);
+            blank.extend(std::iter::repeat_n(0u8, width));
+            blank
+        });
+        history.push(row);
+        ids.push(id);
+    }
+}
+
+// Appends each live process's CPU and drops PIDs that are gone, so the map
+// never holds more than the current process count
+fn record_process_cpu(history: &mut HashMap<u32, (u64, VecDeque<f32>)>, procs: &[ProcessInfo]) {
+    let live: HashMap<u32, u64> = procs.iter().map(|p| (p.pid, p.start_time)).collect();
+    history.retain(|pid, (start, _)| live.get(pid) == Some(start));
+    for p in procs {
+        let (_, cpu) = history.entry(p.pid).or_insert_with(|| (p.start_time, VecDeque::with_capacity(PROCESS_HISTORY_LEN)));
+        if cpu.len() >= PROCESS_HISTORY_LEN { cpu.pop_front(); }
+        cpu.push_back(p.cpu);
+    }
+}
+
+fn push_smoothed(raw: &mut VecDeque<(f64, f64)>, smoothed: &mut VecDeque<(f64, f64)>, point: (f64, f64), alpha: f64, max_len: usize) {
+    let value = match smoothed.back() {
+        Some(&(_, prev)) => alpha * point.1 + (1.0 - alpha) * prev,
+        None => point.1,
+    };
+    if raw.len() >= max_len { raw.pop_front(); }
+    raw.push_back(point);
+    if smoothed.len() >= max_len { smoothed.pop_front(); }
+    smoothed.push_back((point.0, value));
+}
+
+fn ema(raw: &VecDeque<(f64, f64)>, alpha: f64) -> VecDeque<(f64, f64)> {
+    let mut out = VecDeque::with_capacity(raw.capacity());
+    let mut prev: Option<f64> = None;
+    for &(x, v) in raw {
+        let value = prev.map_or(v, |p| alpha * v + (1.0 - alpha) * p);
+        out.push_back((x, value));
+        prev = Some(value);
+    }
+    out
+}
+
+// Trailing mean over up to `window` points; the first few average what there is
+fn moving_average(points: &[(f64, f64)], window: usize) -> Vec<(f64, f64)> {
+    let mut sum = 0.0;
+    points.iter().enumerate().map(|(i, &(x, v))| {
+        sum += v;
+        if i >= window {
+            sum -= points[i - window].1;
+        }
+        (x, sum / (i + 1).min(window) as f64)
+    }).collect()
+}
+
+// Folds processes with the same name (or owner) into one row each. Members keep
+// the order of `procs` (already sorted), groups are sorted by their totals.
+fn build_process_groups(procs: &[ProcessInfo], by: GroupKey, key: ProcessSortKey, ascending: bool) -> Vec<ProcessGroup> {
+    let mut groups: Vec<ProcessGroup> = Vec::new();
+    let mut by_label: HashMap<&str, usize> = HashMap::new();
+    for p in procs {
+        let label = by.label(p);
+        match by_label.get(label) {
+            Some(&i) => {
+                let group = &mut groups[i];
+                let total = &mut group.process;
+                total.cpu += p.cpu;
+                total.mem += p.mem;
+                total.disk_read += p.disk_read;
+                total.disk_write += p.disk_write;
+                total.disk_read_total += p.disk_read_total;
+                total.disk_write_total += p.disk_write_total;
+                total.pid = total.pid.min(p.pid);
+                group.pids.push(p.pid);
+            }
+            None => {
+                by_label.insert(label, groups.len());
+                let mut total = p.clone();
+                total.name = label.to_string();
+                groups.push(ProcessGroup { process: total, pids: vec![p.pid] });
+            }
+        }
+    }
+    groups.sort_by(|a, b| {
+        let ord = key.compare(&a.process, &b.process);
+        if ascending { ord } else { ord.reverse() }
+    });
+    groups
+}
+
+// Arrange processes into parent/child order with CPU/MEM summed up each subtree.
+// Processes whose parent isn't in the snapshot become roots (PID 1, kthreadd, orphans).
+fn build_process_tree(procs: &[ProcessInfo], key: ProcessSortKey, ascending: bool) -> Vec<TreeRow> {
+    let by_pid: HashMap<u32, &ProcessInfo> = procs.iter().map(|p| (p.pid, p)).collect();
+
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut roots = Vec::new();
+    for p in procs {
+        match p.parent_pid {
+            Some(ppid) if ppid != p.pid && by_pid.contains_key(&ppid) => children.entry(ppid).or_default().push(p.pid),
+            _ => roots.push(p.pid),
+        }
+    }
+
+    // Subtree totals (iterative post-order so deep chains can't blow the stack)
+    let mut totals: HashMap<u32, (f32, u64)> = HashMap::new();
+    let mut stack: Vec<(u32, bool)> = roots.iter().map(|&pid| (pid, false)).collect();
+    let mut visited = HashSet::new();
+    while let Some((pid, expanded)) = stack.pop() {
+        if expanded {
+            let own = by_pid[&pid];
+            let (mut cpu, mut mem) = (own.cpu, own.mem);
+            for child in children.get(&pid).into_iter().flatten() {
+                if let Some((c, m)) = totals.get(child) { cpu += c; mem += m; }
+            }
+            totals.insert(pid, (cpu, mem));
+        } else if visited.insert(pid) {
+            stack.push((pid, true));
+            for &child in children.get(&pid).into_iter().flatten() {
+                stack.push((child, false));
+            }
+        }
+    }
+
+    // Siblings are ordered like the flat list, with CPU/MEM compared on subtree totals
+    let order = |a: &u32, b: &u32| {
+        let (ta, tb) = (totals.get(a).copied().unwrap_or_default(), totals.get(b).copied().unwrap_or_default());
+        let ord = match key {
+            ProcessSortKey::Cpu => ta.0.partial_cmp(&tb.0).unwrap_or(std::cmp::Ordering::Equal),
+            ProcessSortKey::Mem => ta.1.cmp(&tb.1),
+            ProcessSortKey::Disk | ProcessSortKey::Pid | ProcessSortKey::Name => key.compare(by_pid[a], by_pid[b]),
+        };
+        if ascending { ord } else { ord.reverse() }
+    };
+    roots.sort_by(order);
+    for list in children.values_mut() {
+        list.sort_by(order);
+    }
+
+    // Pre-order walk. Anything still unvisited sits on a parent cycle and is emitted as a root.
+    let mut rows = Vec::with_capacity(procs.len());
+    let mut emitted = HashSet::new();
+    let leftovers: Vec<u32> = procs.iter().map(|p| p.pid).filter(|pid| !visited.contains(pid)).collect();
+    for root in roots.iter().chain(leftovers.iter()) {
+        let mut stack = vec![(*root, 0)];
+        while let Some((pid, depth)) = stack.pop() {
+            if !emitted.insert(pid) { continue; }
+            let mut process = by_pid[&pid].clone();
+            if let Some((cpu, mem)) = totals.get(&pid) {
+                process.cpu = *cpu;
+                process.mem = *mem;
+            }
+            rows.push(TreeRow { depth, process });
+            for &child in children.get(&pid).into_iter().flatten().rev() {
+                stack.push((child, depth + 1));
+            }
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn sample(cores: &[f32]) -> SystemStats {
+        sample_with_ids(&(0..cores.len()).collect::<Vec<_>>(), cores)
+    }
+
+    fn sample_with_ids(ids: &[usize], cores: &[f32]) -> SystemStats {
+        SystemStats {
+            cpu_usage: cores.to_vec(),
+            cpu_ids: ids.to_vec(),
+            total_cpu_usage: if cores.is_empty() { 0.0 } else { cores.iter().sum::<f32>() / cores.len() as f32 },
+            cpu_breakdown: None,
+            ram_used: 1,
+            ram_total: 2,
+            ram_available: 1,
+            ram_free: 1,
+            ram_buffers: 0,
+            ram_cached: 0,
+            swap_used: 0,
+            swap_total: 0,
+            swap_in_rate: 0.0,
+            swap_out_rate: 0.0,
+            ctx_switch_rate: None,
+            interrupt_rate: None,
+            rx_bytes: 0,
+            tx_bytes: 0,
+            rx_speed: 0,
+            tx_speed: 0,
+            temperatures: Vec::new(),
+            processes: None,
+            disks: Vec::new(),
+            disk_io: Vec::new(),
+            disk_busy: Vec::new(),
+            disk_health: Vec::new(),
+            connections: None,
+            sockets: Vec::new(),
+            cpu_brand: String::new(),
+            physical_cores: None,
+            logical_cores: cores.len(),
+            refresh_ms: 0.0,
+            timestamp: Instant::now(),
+            uptime: 0,
+            load_avg: (0.0, 0.0, 0.0),
+            battery: None,
+        }
+    }
+
+    fn procs_mut(stats: &mut SystemStats) -> &mut Vec<ProcessInfo> {
+        Arc::make_mut(stats.processes.get_or_insert_default())
+    }
+
+    fn temp(name: &str, celsius: f32) -> TempInfo {
+        TempInfo { name: name.to_string(), celsius }
+    }
+
+    pub(crate) fn proc(pid: u32, parent: Option<u32>, cpu: f32) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            name: format!("p{}", pid),
+            cpu,
+            mem: 1,
+            parent_pid: parent,
+            disk_read: 0,
+            disk_write: 0,
+            cmd: String::new(),
+            exe: None,
+            user: None,
+            start_time: 0,
+            threads: None,
+            disk_read_total: 0,
+            disk_write_total: 0,
+            status: String::new(),
+            cwd: None,
+        }
+    }
+
+    fn push_window(app: &mut App, samples: &[&[f32]]) {
+        for cores in samples {
+            app.accumulated_stats.push(sample(cores));
+        }
+        app.update_charts();
+    }
+
+    #[test]
+    fn silent_monitor_is_flagged_stale() {
+        let mut app = App::new(3);
+        let start = app.last_sample_at;
+        let secs = |s: u64| start + std::time::Duration::from_secs(s);
+        assert_eq!(app.stale_for(secs(4)), None);
+        assert_eq!(app.stale_for(secs(6)), Some(std::time::Duration::from_secs(6))); // Never heard from at all
+
+        app.on_tick_at(sample(&[10.0]), secs(6));
+        assert_eq!(app.stale_for(secs(8)), None);
+        assert!(app.stale_for(secs(12)).is_some());
+
+        // A disconnect has its own banner; a replay may legitimately go quiet
+        app.monitor_disconnected = true;
+        assert_eq!(app.stale_for(secs(12)), None);
+        app.monitor_disconnected = false;
+        app.watch_staleness = false;
+        assert_eq!(app.stale_for(secs(12)), None);
+    }
+
+    #[test]
+    fn charts_average_each_interval_on_the_injected_clock() {
+        let mut app = App::new(3);
+        let start = app.last_chart_update;
+        let at = |ms: u64| start + std::time::Duration::from_millis(ms);
+        let tick = |app: &mut App, cores: &[f32], rx: u64, ms: u64| {
+            let mut stats = sample(cores);
+            stats.rx_speed = rx;
+            app.on_tick_at(stats, at(ms));
+        };
+
+        // Samples inside one interval are held back, then averaged together
+        tick(&mut app, &[10.0, 30.0], 100, 40);
+        tick(&mut app, &[50.0, 70.0], 300, 80);
+        assert!(app.cpu_history_total.is_empty());
+        tick(&mut app, &[30.0, 50.0], 200, 100);
+        assert_eq!(app.cpu_history_total, VecDeque::from([(1.0, 40.0)]));
+        assert_eq!((app.cpu_history_min[0].1, app.cpu_history_max[0].1), (20.0, 60.0));
+        assert_eq!(app.net_rx_history, VecDeque::from([(1.0, 200.0)]));
+        assert_eq!(app.ram_history, VecDeque::from([(1.0, 50.0)]));
+        assert_eq!(app.cpu_core_history, vec![VecDeque::from([30]), VecDeque::from([50])]);
+        assert!(app.accumulated_stats.is_empty());
+
+        // One sample per interval from here on; the oldest points fall off at the cap
+        for (i, cpu) in [20.0, 40.0, 60.0, 80.0].into_iter().enumerate() {
+            tick(&mut app, &[cpu, cpu], 0, 200 + 100 * i as u64);
+        }
+        let points: Vec<_> = app.cpu_history_total.iter().copied().collect();
+        assert_eq!(points, vec![(3.0, 40.0), (4.0, 60.0), (5.0, 80.0)]);
+        assert_eq!(app.ram_history.len(), 3);
+        assert_eq!(app.cpu_core_history[0], VecDeque::from([30, 20, 40, 60, 80]));
+    }
+
+    #[test]
+    fn cpu_breakdown_charts_as_running_totals() {
+        let mut app = App::new(3);
+        let split = |user, system, iowait, steal| Some(CpuBreakdown { user, system, iowait, steal });
+        for breakdown in [split(10.0, 4.0, 2.0, 0.0), split(30.0, 6.0, 4.0, 2.0)] {
+            let mut stats = sample(&[50.0]);
+            stats.cpu_breakdown = breakdown;
+            app.accumulated_stats.push(stats);
+        }
+        app.update_charts();
+        let tops: Vec<f64> = app.cpu_stack_history.iter().map(|s| s[0].1).collect();
+        assert_eq!(tops, vec![20.0, 25.0, 28.0, 29.0]);
+
+        // No breakdown (other platforms, old recordings): the stacked chart has nothing to show
+        push_window(&mut app, &[&[50.0]]);
+        assert_eq!(app.cpu_stack_history[0].len(), 1);
+    }
+
+    #[test]
+    fn zero_ram_total_charts_as_zero_not_nan() {
+        let mut app = App::new(10);
+        let mut stats = sample(&[10.0]);
+        stats.ram_total = 0;
+        app.accumulated_stats.push(stats);
+        app.update_charts();
+        assert_eq!(app.ram_history, VecDeque::from([(1.0, 0.0)]));
+        assert_eq!(app.ram_raw, VecDeque::from([(1.0, 0.0)]));
+    }
+
+    #[test]
+    fn heatmap_realigns_when_core_count_changes() {
+        let mut app = App::new(10);
+        push_window(&mut app, &[&[10.0, 20.0], &[30.0, 40.0]]);
+        push_window(&mut app, &[&[50.0, 60.0]]);
+        assert_eq!(app.cpu_core_history.len(), 2);
+        assert_eq!(app.cpu_core_history[0], VecDeque::from([20, 50]));
+
+        // A core comes online mid-window: existing rows keep their history,
+        // the new row is back-filled so all rows share the same time axis.
+        push_window(&mut app, &[&[70.0, 80.0], &[90.0, 100.0, 40.0]]);
+        assert_eq!(app.cpu_core_history.len(), 3);
+        assert_eq!(app.cpu_core_history[0], VecDeque::from([20, 50, 80]));
+        assert_eq!(app.cpu_core_history[1], VecDeque::from([30, 60, 90]));
+        assert_eq!(app.cpu_core_history[2], VecDeque::from([0, 0, 40]));
+
+        // Going offline trims the row without touching the others
+        push_window(&mut app, &[&[5.0]]);
+        assert_eq!(app.cpu_core_history.len(), 1);
+        assert_eq!(app.cpu_core_history[0], VecDeque::from([20, 50, 80, 5]));
+    }
+
+    #[test]
+    fn heatmap_keeps_history_by_core_id_across_hotplug() {
+        let mut app = App::new(10);
+        let mut push = |ids: &[usize], cores: &[f32]| {
+            app.accumulated_stats.push(sample_with_ids(ids, cores));
+            app.update_charts();
+        };
+        push(&[0, 1, 2, 3], &[10.0, 20.0, 30.0, 40.0]);
+        // cpu1 goes offline: cpu2 and cpu3 shift down in position but keep their rows
+        push(&[0, 2, 3], &[11.0, 31.0, 41.0]);
+        // A different core count comes back with a new core
+        push(&[0, 1, 3, 4], &[12.0, 22.0, 42.0, 52.0]);
+
+        assert_eq!(app.cpu_core_ids, vec![0, 1, 3, 4]);
+        assert_eq!(app.cpu_core_history[0], VecDeque::from([10, 11, 12]));
+        assert_eq!(app.cpu_core_history[1], VecDeque::from([0, 0, 22])); // History lost while offline
+        assert_eq!(app.cpu_core_history[2], VecDeque::from([40, 41, 42]));
+        assert_eq!(app.cpu_core_history[3], VecDeque::from([0, 0, 52]));
+    }
+
+    #[test]
+    fn heatmap_history_follows_canvas_width() {
+        let mut app = App::new(10);
+        push_window(&mut app, &[&[10.0]]);
+        push_window(&mut app, &[&[20.0]]);
+        push_window(&mut app, &[&[30.0]]);
+
+        // Terminal shrank to two columns: older samples are dropped on the next update
+        app.heatmap_columns.set(2);
+        push_window(&mut app, &[&[40.0]]);
+        assert_eq!(app.cpu_core_history[0], VecDeque::from([30, 40]));
+    }
+
+    #[test]
+    fn fixed_heatmap_width_is_independent_of_chart_history() {
+        let mut app = App::new(4);
+        app.heatmap_fixed_width = Some(2);
+        app.heatmap_columns.set(80); // Ignored while the width is fixed
+        for cpu in [10.0, 20.0, 30.0, 40.0, 50.0] {
+            push_window(&mut app, &[&[cpu]]);
+        }
+        assert_eq!(app.cpu_core_history[0], VecDeque::from([40, 50]));
+        assert_eq!(app.cpu_history_total.len(), 4);
+    }
+
+    #[test]
+    fn moving_average_only_smooths_the_rendered_points() {
+        let mut app = App::new(CHART_HISTORY_LEN);
+        let history: VecDeque<(f64, f64)> = (0..251).map(|i| (i as f64, if i % 2 == 0 { 0.0 } else { 10.0 })).collect();
+        assert_eq!(app.chart_points(ChartId::Cpu, &history)[0].1, 10.0); // Off: the raw span
+
+        app.moving_average_window = 2;
+        app.moving_average_charts = vec![ChartId::Cpu];
+        app.moving_average_enabled = true;
+        let points = app.chart_points(ChartId::Cpu, &history);
+        assert_eq!(points.len(), 200); // Same 20s span as without averaging
+        assert_eq!(points.first(), Some(&(51.0, 5.0))); // Averaged with the point before the span
+        assert!(points.iter().all(|&(_, v)| v == 5.0));
+        assert_eq!(app.chart_points(ChartId::Ram, &history)[0].1, 10.0); // Other charts untouched
+        assert_eq!(history[51], (51.0, 10.0));
+        assert_eq!(moving_average(&[(0.0, 3.0), (1.0, 6.0), (2.0, 9.0)], 5), vec![(0.0, 3.0), (1.0, 4.5), (2.0, 6.0)]);
+    }
+
+    #[test]
+    fn temp_history_skips_windows_without_sensors() {
+        let mut app = App::new(10);
+        push_window(&mut app, &[&[10.0]]);
+        assert!(app.temp_history.is_empty());
+
+        let mut hot = sample(&[10.0]);
+        hot.temperatures = vec![temp("cpu", 40.0), temp("nvme", 55.0)];
+        app.accumulated_stats.push(hot);
+        app.update_charts();
+        assert_eq!(app.temp_history.back().map(|p| p.1), Some(55.0));
+    }
+
+    #[test]
+    fn temp_history_shares_the_chart_clock_and_cap() {
+        let mut app = App::new(2);
+        for t in [40.0, 50.0, 60.0] {
+            let mut s = sample(&[10.0]);
+            s.temperatures = vec![temp("cpu", t)];
+            app.accumulated_stats.push(s);
+            app.update_charts();
+        }
+        assert_eq!(app.temp_history.len(), 2);
+        assert_eq!(app.temp_history.back().map(|p| p.0), app.cpu_history_total.back().map(|p| p.0));
+        assert_eq!(app.temp_history.iter().map(|p| p.1).collect::<Vec<_>>(), vec![50.0, 60.0]);
+    }
+
+    #[test]
+    fn solaris_mode_divides_process_cpu_by_core_count() {
+        let mut app = App::new(10);
+        app.on_tick(sample(&[10.0, 20.0, 30.0, 40.0]));
+        assert_eq!(app.process_cpu(200.0), 200.0);
+        app.perform(Action::ToggleIrixMode);
+        assert_eq!(app.process_cpu(200.0), 50.0);
+    }
+
+    #[test]
+    fn temp_view_cycles_through_each_sensor() {
+        let mut app = App::new(10);
+        app.perform(Action::CycleTempView);
+        assert_eq!(app.temp_view, TempView::Max, "nothing to cycle without sensors");
+
+        let mut hot = sample(&[10.0]);
+        hot.temperatures = vec![temp("cpu", 40.0), temp("nvme", 55.0)];
+        app.on_tick(hot.clone());
+        app.accumulated_stats.push(hot);
+        app.update_charts();
+        assert_eq!(app.sensor_history["cpu"].back().map(|p| p.1), Some(40.0));
+        assert_eq!(app.hottest_sensors(1), vec!["nvme"]);
+
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            app.perform(Action::CycleTempView);
+            seen.push(app.temp_view.clone());
+        }
+        assert_eq!(seen, vec![
+            TempView::Sensor("cpu".to_string()),
+            TempView::Sensor("nvme".to_string()),
+            TempView::Hottest,
+            TempView::Max,
+        ]);
+    }
+
+    #[test]
+    fn process_tree_aggregates_into_ancestors() {
+        // 1 -> 2 -> 3, plus 4 whose parent (99) isn't in the snapshot
+        let procs = [proc(3, Some(2), 30.0), proc(1, None, 1.0), proc(2, Some(1), 5.0), proc(4, Some(99), 50.0)];
+        let rows = build_process_tree(&procs, ProcessSortKey::Cpu, false);
+        let order: Vec<(u32, usize, f32)> = rows.iter().map(|r| (r.process.pid, r.depth, r.process.cpu)).collect();
+        assert_eq!(order, vec![(4, 0, 50.0), (1, 0, 36.0), (2, 1, 35.0), (3, 2, 30.0)]);
+    }
+
+    #[test]
+    fn process_tree_survives_parent_cycles() {
+        // 5 and 6 claim each other as parent; both must still show up exactly once
+        let procs = [proc(5, Some(6), 1.0), proc(6, Some(5), 2.0), proc(7, Some(7), 3.0)];
+        let rows = build_process_tree(&procs, ProcessSortKey::Pid, true);
+        let mut pids: Vec<u32> = rows.iter().map(|r| r.process.pid).collect();
+        pids.sort();
+        assert_eq!(pids, vec![5, 6, 7]);
+    }
+
+    #[test]
+    fn swap_pressure_needs_high_and_growing_swap() {
+        let mut app = App::new(10);
+        let start = Instant::now();
+        let mut at = |secs: u64, used_mb: u64| {
+            let mut s = sample(&[10.0]);
+            s.timestamp = start + std::time::Duration::from_secs(secs);
+            (s.swap_used, s.swap_total) = (used_mb << 20, 100 << 20);
+            app.last_stats = Some(s.clone());
+            app.accumulated_stats.push(s);
+            app.update_charts();
+            app.swap_pressure()
+        };
+        assert_eq!(at(0, 60), None);             // No rate yet
+        assert_eq!(at(1, 62), Some(62.0));       // 62% and +2 MB/s
+        assert_eq!(at(2, 62), Some(62.0));       // Still +1 MB/s over the window
+        assert_eq!(at(8, 62), None);             // Flat over the last 5s
+    }
+
+    #[test]
+    fn heatmap_skips_samples_without_cores() {
+        let mut app = App::new(10);
+        push_window(&mut app, &[&[]]);
+        assert!(app.cpu_core_history.is_empty());
+
+        push_window(&mut app, &[&[40.0], &[]]);
+        assert_eq!(app.cpu_core_history[0], VecDeque::from([40]));
+    }
+
+    #[test]
+    fn chart_zoom_only_changes_the_rendered_slice() {
+        let mut app = App::new(CHART_HISTORY_LEN);
+        let history: VecDeque<(f64, f64)> = (0..1000).map(|i| (i as f64, 0.0)).collect();
+        assert_eq!(app.chart_window(&history).len(), 200); // 20s at 10 points/s
+        assert_eq!(app.chart_window(&history).next(), Some(&(800.0, 0.0)));
+
+        app.perform(Action::ZoomIn);
+        app.perform(Action::ZoomIn);
+        assert_eq!((app.chart_span_label(), app.chart_window(&history).len()), ("10s".to_string(), 100));
+
+        for _ in 0..10 { app.perform(Action::ZoomOut); }
+        assert_eq!((app.chart_span_label(), app.chart_window(&history).len()), ("5m".to_string(), 1000));
+        assert_eq!(history.len(), 1000);
+    }
+
+    #[test]
+    fn process_cpu_history_evicts_exited_and_reused_pids() {
+        let mut history = HashMap::new();
+        record_process_cpu(&mut history, &[proc(1, None, 10.0), proc(2, None, 20.0)]);
+        record_process_cpu(&mut history, &[proc(1, None, 15.0)]);
+        assert_eq!(history.get(&1).map(|(_, c)| c.clone()), Some(VecDeque::from([10.0, 15.0])));
+        assert!(!history.contains_key(&2));
+
+        // Same PID, different process: starts over
+        let mut reused = proc(1, None, 50.0);
+        reused.start_time = 99;
+        record_process_cpu(&mut history, &[reused]);
+        assert_eq!(history.get(&1).map(|(_, c)| c.clone()), Some(VecDeque::from([50.0])));
+
+        for _ in 0..PROCESS_HISTORY_LEN * 2 {
+            record_process_cpu(&mut history, &[proc(3, None, 1.0)]);
+        }
+        assert_eq!(history[&3].1.len(), PROCESS_HISTORY_LEN);
+    }
+
+    #[test]
+    fn column_list_parses_and_rejects_unknown_names() {
+        assert_eq!(Column::parse_list("pid, NAME,cpu,state").unwrap(), vec![Column::Pid, Column::Name, Column::Cpu, Column::State]);
+        let err = Column::parse_list("pid,nice").unwrap_err().to_string();
+        assert!(err.contains("'nice'") && err.contains("user, state, start"), "{}", err);
+        assert!(Column::parse_list(",").is_err());
+    }
+
+    #[test]
+    fn process_groups_sum_usage_by_name() {
+        let mut procs = vec![proc(10, None, 5.0), proc(11, None, 30.0), proc(12, None, 20.0), proc(13, None, 1.0)];
+        for (p, name) in procs.iter_mut().zip(["chrome", "java", "chrome", "chrome"]) {
+            p.name = name.to_string();
+        }
+        let groups = build_process_groups(&procs, GroupKey::Name, ProcessSortKey::Cpu, false);
+        let rows: Vec<(&str, u32, f32, usize)> = groups.iter().map(|g| (g.process.name.as_str(), g.process.pid, g.process.cpu, g.pids.len())).collect();
+        assert_eq!(rows, vec![("java", 11, 30.0, 1), ("chrome", 10, 26.0, 3)]);
+        assert_eq!(groups[1].process.mem, 3);
+        assert_eq!(groups[1].pids, vec![10, 12, 13]);
+    }
+
+    #[test]
+    fn process_groups_sum_usage_by_user() {
+        let mut procs = vec![proc(10, None, 5.0), proc(11, None, 30.0), proc(12, None, 20.0), proc(13, None, 1.0)];
+        for (p, user) in procs.iter_mut().zip([Some("alice"), Some("bob"), Some("alice"), None]) {
+            p.user = user.map(str::to_string);
+        }
+        let groups = build_process_groups(&procs, GroupKey::User, ProcessSortKey::Cpu, false);
+        let rows: Vec<(&str, f32, usize)> = groups.iter().map(|g| (g.process.name.as_str(), g.process.cpu, g.pids.len())).collect();
+        assert_eq!(rows, vec![("bob", 30.0, 1), ("alice", 25.0, 2), ("?", 1.0, 1)]);
+
+        let mut app = App::new(10);
+        app.perform(Action::ToggleUserGroup);
+        assert!(app.process_grouped && app.group_key == GroupKey::User);
+        app.perform(Action::ToggleGroup);
+        assert!(app.process_grouped && app.group_key == GroupKey::Name);
+        app.perform(Action::ToggleGroup);
+        assert!(!app.process_grouped);
+    }
+
+    #[test]
+    fn chart_summaries_track_since_launch_until_reset() {
+        let mut app = App::new(10);
+        push_window(&mut app, &[&[10.0]]);
+        push_window(&mut app, &[&[50.0], &[30.0]]);
+        push_window(&mut app, &[&[20.0]]);
+        assert_eq!((app.cpu_summary.min, app.cpu_summary.max), (10.0, 40.0));
+        assert_eq!(app.cpu_summary.avg(), Some(70.0 / 3.0));
+        assert_eq!(app.temp_summary.avg(), None);
+
+        app.perform(Action::ResetSummaries);
+        assert_eq!(app.cpu_summary.avg(), None);
+        push_window(&mut app, &[&[5.0]]);
+        assert_eq!((app.cpu_summary.min, app.cpu_summary.max), (5.0, 5.0));
+    }
+
+    #[test]
+    fn hide_kernel_keeps_idle_user_programs() {
+        let mut app = App::new(10);
+        let mut stats = sample(&[10.0]);
+        let mut idle = proc(0, None, 90.0);
+        idle.name = "System Idle Process".to_string();
+        let mut kthread = proc(7, None, 0.0);
+        kthread.mem = 0;
+        let quiet = proc(8, None, 0.0); // Some memory, no CPU: a real program sitting idle
+        stats.processes = Some(Arc::new(vec![idle, kthread, quiet, proc(9, None, 5.0)]));
+        app.on_tick(stats);
+        assert_eq!(app.processes.len(), 4);
+
+        app.perform(Action::ToggleKernel);
+        assert_eq!(app.processes.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![9, 8]);
+        app.perform(Action::ToggleKernel);
+        assert_eq!(app.processes.len(), 4);
+    }
+
+    #[test]
+    fn fast_samples_keep_the_last_process_list() {
+        let mut app = App::new(10);
+        let mut stats = sample(&[10.0]);
+        stats.processes = Some(Arc::new(vec![proc(1, None, 1.0), proc(2, None, 2.0)]));
+        app.on_tick(stats.clone());
+        app.process_scroll_state = 1;
+
+        stats.processes = None;
+        app.on_tick(stats);
+        assert_eq!(app.processes.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(app.all_processes.len(), 2);
+        assert_eq!(app.process_scroll_state, 1);
+    }
+
+    #[test]
+    fn pinned_names_stay_on_top_across_restarts() {
+        let mut app = App::new(10);
+        let mut stats = sample(&[10.0]);
+        stats.processes = Some(Arc::new(vec![proc(1, None, 1.0), proc(2, None, 2.0), proc(3, None, 3.0)]));
+        app.on_tick(stats.clone());
+        app.process_scroll_state = 2; // PID 1, sorted by CPU descending
+        app.perform(Action::TogglePin);
+        assert!(app.pinned.contains("p1"));
+        assert_eq!(app.processes.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![1, 3, 2]);
+
+        // The daemon restarts under a new PID but keeps its name
+        procs_mut(&mut stats)[0].pid = 40;
+        app.on_tick(stats);
+        assert_eq!(app.processes.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![40, 3, 2]);
+
+        app.process_scroll_state = 0;
+        app.perform(Action::TogglePin);
+        assert!(app.pinned.is_empty());
+        assert_eq!(app.processes[0].pid, 3);
+    }
+
+    #[test]
+    fn user_groups_cannot_be_pinned() {
+        let mut app = App::new(10);
+        let mut stats = sample(&[10.0]);
+        stats.processes = Some(Arc::new(vec![proc(1, None, 5.0), proc(2, None, 2.0)]));
+        for (p, user) in procs_mut(&mut stats).iter_mut().zip(["root", "alice"]) {
+            p.user = Some(user.to_string());
+        }
+        procs_mut(&mut stats)[0].name = "alice".to_string(); // Root's process happens to share the user's name
+        app.on_tick(stats.clone());
+        app.perform(Action::ToggleUserGroup);
+        app.group_scroll_state = 1; // The "alice" user group, after root's by CPU
+        app.perform(Action::TogglePin);
+        assert!(app.pinned.is_empty());
+        assert!(app.footer_message.as_ref().is_some_and(|(m, _)| m.starts_with("PINS ARE BY PROCESS NAME")));
+
+        // A pinned process name doesn't lift the user group of the same name either
+        app.pinned.insert("alice".to_string());
+        app.on_tick(stats);
+        assert_eq!(app.process_groups.iter().map(|g| g.process.name.as_str()).collect::<Vec<_>>(), vec!["root", "alice"]);
+    }
+
+    #[test]
+    fn owner_filter_hides_other_users() {
+        let mut app = App::new(10);
+        let mut stats = sample(&[10.0]);
+        stats.processes = Some(Arc::new(vec![proc(1, None, 1.0), proc(2, None, 2.0), proc(3, None, 3.0)]));
+        for (p, user) in procs_mut(&mut stats).iter_mut().zip(["root", "alice", "alice"]) {
+            p.user = Some(user.to_string());
+        }
+        app.on_tick(stats.clone());
+        app.process_scroll_state = 1; // PID 2, sorted by CPU descending
+        app.perform(Action::FilterByOwner);
+        assert_eq!(app.user_filter.as_deref(), Some("alice"));
+        assert_eq!(app.processes.iter().map(|p| p.pid).collect::<Vec<_>>(), vec![3, 2]);
+
+        app.on_tick(stats);
+        assert_eq!(app.processes.len(), 2);
+        app.perform(Action::FilterByOwner);
+        assert_eq!(app.processes.len(), 3);
+    }
+
+    #[test]
+    fn session_peaks_keep_the_highest_sample() {
+        let mut app = App::new(10);
+        for (cpu, ram) in [(40.0, 1), (90.0, 1), (10.0, 2)] {
+            let mut stats = sample(&[cpu]);
+            stats.ram_used = ram;
+            app.on_tick(stats);
+        }
+        assert_eq!(app.peak_cpu, 90.0);
+        assert_eq!(app.peak_ram, 100.0);
+        app.perform(Action::ResetSummaries);
+        assert_eq!(app.peak_cpu, 90.0);
+    }
+
+    #[test]
+    fn chart_markers_scroll_off_with_history() {
+        let mut app = App::new(3);
+        push_window(&mut app, &[&[10.0]]);
+        app.perform(Action::AddMarker);
+        assert_eq!(app.chart_markers, vec![1.0]);
+        for _ in 0..2 {
+            push_window(&mut app, &[&[10.0]]);
+        }
+        assert_eq!(app.chart_markers, vec![1.0]); // Still the oldest point on screen
+        push_window(&mut app, &[&[10.0]]);
+        assert!(app.chart_markers.is_empty());
+
+        app.perform(Action::AddMarker);
+        app.perform(Action::ClearMarkers);
+        assert!(app.chart_markers.is_empty());
+    }
+
+    #[test]
+    fn page_keys_move_by_the_visible_rows() {
+        let mut app = App::new(10);
+        let mut stats = sample(&[10.0]);
+        stats.processes = Some(Arc::new((1..=50).map(|pid| proc(pid, None, pid as f32)).collect()));
+        app.on_tick(stats);
+        app.process_table_layout.borrow_mut().area.height = 10 + PROCESS_TABLE_HEADER_ROWS;
+
+        let key = |code| KeyEvent::new(code, crossterm::event::KeyModifiers::NONE);
+        app.on_key(key(KeyCode::PageDown));
+        assert_eq!(app.process_scroll_state, 10);
+        app.on_key(key(KeyCode::End));
+        assert_eq!(app.process_scroll_state, 49);
+        app.on_key(key(KeyCode::PageDown)); // Clamped at the last row
+        assert_eq!(app.process_scroll_state, 49);
+        app.on_key(key(KeyCode::PageUp));
+        assert_eq!(app.process_scroll_state, 39);
+        app.on_key(key(KeyCode::Home));
+        assert_eq!(app.process_scroll_state, 0);
+    }
+
+    #[test]
+    fn locked_sort_keeps_rows_in_place() {
+        let mut app = App::new(10);
+        let tick = |app: &mut App, cpus: &[(u32, f32)]| {
+            let mut stats = sample(&[10.0]);
+            stats.processes = Some(Arc::new(cpus.iter().map(|&(pid, cpu)| proc(pid, None, cpu)).collect()));
+            app.on_tick(stats);
+        };
+        let pids = |app: &App| app.processes.iter().map(|p| p.pid).collect::<Vec<_>>();
+        tick(&mut app, &[(1, 30.0), (2, 20.0), (3, 10.0)]);
+        assert_eq!(pids(&app), [1, 2, 3]);
+
+        app.perform(Action::ToggleSortLock);
+        tick(&mut app, &[(1, 5.0), (3, 50.0), (4, 40.0), (5, 60.0)]);
+        assert_eq!(pids(&app), [1, 3, 5, 4]); // Gone PID dropped, newcomers appended by CPU
+        assert_eq!(app.processes[1].cpu, 50.0); // Values still refresh
+
+        app.perform(Action::ToggleSortLock);
+        assert_eq!(pids(&app), [5, 3, 4, 1]);
+    }
+
+    #[test]
+    fn disk_scroll_stops_at_the_last_page() {
+        let mut app = App::new(10);
+        let mut stats = sample(&[10.0]);
+        stats.disks = (0..5).map(|i| DiskInfo { name: format!("sd{}", i), used: 1, total: 2, fs: "ext4".into(), mount: "/".into() }).collect();
+        app.on_tick(stats);
+        app.disk_rows.set(3);
+        for _ in 0..5 {
+            app.perform(Action::ScrollDisksDown);
+        }
+        assert_eq!(app.disk_scroll, 2);
+        app.perform(Action::ScrollDisksUp);
+        assert_eq!(app.disk_scroll, 1);
+    }
+
+    #[test]
+    fn tab_moves_navigation_between_panels() {
+        let mut app = App::new(10);
+        let mut stats = sample(&[10.0]);
+        stats.processes = Some(Arc::new((1..=5).map(|pid| proc(pid, None, pid as f32)).collect()));
+        stats.disks = (0..5).map(|i| DiskInfo { name: format!("sd{}", i), used: 1, total: 2, fs: "ext4".into(), mount: "/".into() }).collect();
+        app.on_tick(stats);
+        app.disk_rows.set(2);
+
+        let key = |code| KeyEvent::new(code, crossterm::event::KeyModifiers::NONE);
+        app.on_key(key(KeyCode::Tab));
+        assert_eq!(app.focused_panel, FocusPanel::Disks);
+        app.on_key(key(KeyCode::Char('j')));
+        app.on_key(key(KeyCode::End));
+        assert_eq!((app.disk_scroll, app.process_scroll_state), (3, 0));
+        app.on_key(key(KeyCode::Tab)); // Connections aren't open, so back to processes
+        assert_eq!(app.focused_panel, FocusPanel::Processes);
+        app.on_key(key(KeyCode::Char('j')));
+        assert_eq!((app.disk_scroll, app.process_scroll_state), (3, 1));
+
+        app.on_key(key(KeyCode::Char('N')));
+        assert_eq!(app.focused_panel, FocusPanel::Connections);
+        app.on_key(key(KeyCode::Char('q'))); // Closes the table instead of quitting
+        assert!(!app.show_sockets && !app.should_quit);
+        assert_eq!(app.focused_panel, FocusPanel::Processes);
+    }
+
+    #[test]
+    fn storage_table_sorts_and_scrolls_while_focused() {
+        let mut app = App::new(10);
+        let mut stats = sample(&[10.0]);
+        let disk = |mount: &str, used, total| DiskInfo { name: "sda".into(), used, total, fs: "ext4".into(), mount: mount.into() };
+        stats.disks = vec![disk("/", 50, 100), disk("/data", 900, 1000), disk("/boot", 9, 10)];
+        app.on_tick(stats);
+        let mounts = |app: &App| app.storage_disks().iter().map(|d| d.mount.clone()).collect::<Vec<_>>();
+
+        let key = |code| KeyEvent::new(code, crossterm::event::KeyModifiers::NONE);
+        app.on_key(key(KeyCode::Char('D')));
+        assert_eq!(app.focused_panel, FocusPanel::Storage);
+        assert_eq!(mounts(&app), ["/data", "/boot", "/"]); // 90%, 90%, 50%: stable for ties
+        app.on_key(key(KeyCode::Char('s')));
+        assert_eq!(mounts(&app), ["/data", "/", "/boot"]);
+        assert_eq!(app.process_sort_key, ProcessSortKey::Cpu); // Process sort untouched
+        app.on_key(key(KeyCode::Char('s')));
+        assert_eq!(mounts(&app), ["/", "/boot", "/data"]);
+
+        app.on_key(key(KeyCode::End));
+        assert_eq!(app.storage_scroll, 2);
+        app.on_key(key(KeyCode::Char('q'))); // Closes the table instead of quitting
+        assert!(!app.show_storage && !app.should_quit);
+        assert_eq!(app.focused_panel, FocusPanel::Processes);
+    }
+
+    #[test]
+    fn detail_overlay_stays_on_the_process_it_opened_for() {
+        let mut app = App::new(10);
+        let mut stats = sample(&[10.0]);
+        stats.processes = Some(Arc::new((1..=3).map(|pid| proc(pid, None, pid as f32)).collect()));
+        app.on_tick(stats);
+        assert_eq!(app.selected_process().map(|p| p.pid), Some(3)); // Sorted by CPU, highest first
+
+        let key = |code| KeyEvent::new(code, crossterm::event::KeyModifiers::NONE);
+        app.on_key(key(KeyCode::Enter));
+        assert_eq!(app.show_process_detail, Some(3));
+
+        // PID 1 jumps to the top; the overlay keeps showing PID 3
+        let mut stats = sample(&[10.0]);
+        stats.processes = Some(Arc::new(vec![proc(1, None, 50.0), proc(2, None, 2.0), proc(3, None, 3.0)]));
+        app.on_tick(stats);
+        assert_eq!(app.selected_process().map(|p| p.pid), Some(1));
+        assert_eq!(app.show_process_detail, Some(3));
+
+        app.on_key(key(KeyCode::Esc));
+        assert_eq!(app.show_process_detail, None);
+    }
+
+    #[test]
+    fn warns_once_when_most_processes_are_unreadable() {
+        let readable = |pid| ProcessInfo { exe: Some("/bin/sh".into()), cmd: "sh".into(), ..proc(pid, None, 0.0) };
+        let mut app = App::new(10);
+        let mut stats = sample(&[10.0]);
+        stats.processes = Some(Arc::new(vec![readable(1), readable(2), proc(3, None, 0.0), proc(4, None, 0.0)]));
+        app.on_tick(stats.clone());
+        assert_eq!(app.active_footer_message(), Some("2 OF 4 PROCESSES NOT READABLE: RUN WITH MORE PRIVILEGES FOR FULL DETAILS AND SIGNALS"));
+
+        let mut app = App::new(10);
+        stats.processes = Some(Arc::new(vec![readable(1), readable(2), readable(3), readable(4), proc(5, None, 0.0)]));
+        app.on_tick(stats);
+        assert_eq!(app.active_footer_message(), None);
+    }
+
+    #[test]
+    fn top_limit_caps_the_flat_list_and_selection() {
+        let mut app = App::new(10);
+        let mut stats = sample(&[10.0]);
+        stats.processes = Some(Arc::new((1..=5).map(|pid| proc(pid, None, pid as f32)).collect()));
+        app.on_tick(stats);
+        assert_eq!(app.listed_processes(), 5);
+
+        app.process_limit = Some(3);
+        assert_eq!(app.listed_processes(), 3);
+        app.perform(Action::Bottom);
+        assert_eq!(app.process_scroll_state, 2);
+        app.perform(Action::Down);
+        assert_eq!(app.process_scroll_state, 2);
+    }
+
+    #[test]
+    fn top_limit_applies_after_sorting_the_full_list() {
+        let mut app = App::new(10);
+        app.process_limit = Some(2);
+        let mut stats = sample(&[10.0]);
+        // The biggest memory users are the idlest, so a CPU-first cut would hide them
+        stats.processes = Some(Arc::new((1..=5).map(|pid| ProcessInfo { mem: pid as u64 * 100, ..proc(pid, None, 10.0 - pid as f32) }).collect()));
+        app.on_tick(stats);
+        let listed = |app: &App| app.processes[..app.listed_processes()].iter().map(|p| p.pid).collect::<Vec<_>>();
+        assert_eq!(listed(&app), vec![1, 2]);
+
+        app.perform(Action::CycleSort); // CPU -> MEM
+        assert_eq!(app.process_sort_key, ProcessSortKey::Mem);
+        assert_eq!(listed(&app), vec![5, 4]);
+    }
+
+    #[test]
+    fn open_tables_keep_process_keys_from_the_list_behind() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut app = App::new(10);
+        app.commands = Some(tx);
+        let mut stats = sample(&[10.0]);
+        stats.processes = Some(Arc::new(vec![proc(42, None, 1.0)]));
+        stats.disks = vec![DiskInfo { name: "sda".into(), used: 1, total: 2, fs: "ext4".into(), mount: "/".into() }];
+        app.on_tick(stats);
+
+        let key = |code| KeyEvent::new(code, crossterm::event::KeyModifiers::NONE);
+        app.on_key(key(KeyCode::Char('N')));
+        assert!(matches!(rx.try_recv(), Ok(MonitorCommand::WatchSockets(true))));
+        for code in [KeyCode::Char('z'), KeyCode::Char('x'), KeyCode::Enter, KeyCode::Char('P'), KeyCode::Tab] {
+            app.on_key(key(code));
+        }
+        assert!(rx.try_recv().is_err());
+        assert!(app.kill_menu.is_none() && app.show_process_detail.is_none() && app.pinned.is_empty());
+        assert_eq!(app.focused_panel, FocusPanel::Connections);
+
+        app.on_key(key(KeyCode::Char('D'))); // Swaps to the storage table
+        app.on_key(key(KeyCode::Char('z')));
+        assert!(app.show_storage && !app.show_sockets);
+        assert!(matches!(rx.try_iter().last(), Some(MonitorCommand::WatchSockets(false))));
+    }
+
+    #[test]
+    fn kill_menu_sends_the_chosen_signal() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut app = App::new(10);
+        app.commands = Some(tx);
+        let mut stats = sample(&[10.0]);
+        stats.processes = Some(Arc::new(vec![proc(42, None, 1.0)]));
+        app.on_tick(stats);
+
+        let key = |code| KeyEvent::new(code, crossterm::event::KeyModifiers::NONE);
+        app.on_key(key(KeyCode::Char('x')));
+        assert_eq!(app.kill_menu.as_ref().map(|m| m.pid), Some(42));
+        app.on_key(key(KeyCode::Down));
+        app.on_key(key(KeyCode::Char('q'))); // Closes the menu instead of quitting
+        assert!(app.kill_menu.is_none() && !app.should_quit);
+
+        app.on_key(key(KeyCode::Char('x')));
+        app.on_key(key(KeyCode::Down));
+        app.on_key(key(KeyCode::Enter));
+        assert!(matches!(rx.try_recv(), Ok(MonitorCommand::Kill { pid: 42, signal: KillSignal::Kill })));
+
+        app.on_key(key(KeyCode::Char('z')));
+        assert!(matches!(rx.try_recv(), Ok(MonitorCommand::Kill { pid: 42, signal: KillSignal::Stop })));
+        app.on_key(key(KeyCode::Char('Z')));
+        assert!(matches!(rx.try_recv(), Ok(MonitorCommand::Kill { pid: 42, signal: KillSignal::Cont })));
+    }
+
+    #[test]
+    fn history_round_trips_and_rejects_other_versions() {
+        let path = std::env::temp_dir().join(format!("mk05-history-test-{}.json", std::process::id()));
+        let mut app = App::new(10);
+        for cpu in [10.0, 20.0, 30.0] {
+            let mut stats = sample(&[cpu]);
+            stats.cpu_breakdown = Some(CpuBreakdown { user: cpu, system: 1.0, iowait: 0.0, steal: 0.0 });
+            app.accumulated_stats.push(stats);
+            app.update_charts();
+        }
+        app.save_state(&path).unwrap();
+
+        let mut restored = App::new(2);
+        assert!(restored.load_state(&path).unwrap());
+        assert_eq!(restored.cpu_history_total, VecDeque::from([(2.0, 20.0), (3.0, 30.0)]));
+        assert_eq!(restored.cpu_stack_history[1], VecDeque::from([(2.0, 21.0), (3.0, 31.0)])); // Stacked chart restored with it
+        assert_eq!(restored.chart_tick_count, 3.0);
+
+        std::fs::write(&path, r#"{"version": 0, "cpu": []}"#).unwrap();
+        assert!(!App::new(10).load_state(&path).unwrap());
+        std::fs::write(&path, "not json").unwrap();
+        assert!(!App::new(10).load_state(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(not(feature = "battery"), allow(dead_code))]
+pub enum ChargeState {
+    Charging,
+    Discharging,
+    Full,
+    Unknown,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BatteryInfo {
+    pub percent: f32,
+    pub state: ChargeState,
+    pub time_to_full: Option<Duration>,
+    pub time_to_empty: Option<Duration>,
+}
+
+// Reads the first system battery. Always `None` without the `battery` feature
+// or on machines that don't have one.
+pub struct BatteryReader {
+    #[cfg(feature = "battery")]
+    manager: Option<::battery::Manager>,
+}
+
+impl BatteryReader {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(feature = "battery")]
+            manager: ::battery::Manager::new().ok(),
+        }
+    }
+
+    #[cfg(feature = "battery")]
+    pub fn read(&mut self) -> Option<BatteryInfo> {
+        use ::battery::units::ratio::percent;
+        use ::battery::units::time::second;
+
+        let manager = self.manager.as_ref()?;
+        let battery = manager.batteries().ok()?.flatten().next()?;
+
+        let state = match battery.state() {
+            ::battery::State::Charging => ChargeState::Charging,
+            ::battery::State::Discharging => ChargeState::Discharging,
+            ::battery::State::Full => ChargeState::Full,
+            _ => ChargeState::Unknown,
+        };
+        let to_duration = |t: ::battery::units::Time| Duration::from_secs_f32(t.get::<second>().max(0.0));
+
+        Some(BatteryInfo {
+            percent: battery.state_of_charge().get::<percent>(),
+            state,
+            time_to_full: battery.time_to_full().map(to_duration),
+            time_to_empty: battery.time_to_empty().map(to_duration),
+        })
+    }
+
+    #[cfg(not(feature = "battery"))]
+    pub fn read(&mut self) -> Option<BatteryInfo> {
+        None
+    }
+}
@@ -0,0 +1,248 @@
+use std::path::PathBuf;
+use anyhow::{bail, Context, Result};
+use crate::alerts::{AlertMetric, AlertRule};
+use crate::app::{ChartId, Column};
+use crate::theme::{self, Palette};
+use ratatui::style::Color;
+use crate::units::{TempUnit, UnitMode};
+
+// Slowest --replay-speed; anything below it would sleep for days between samples
+const MIN_REPLAY_SPEED: f64 = 0.01;
+
+// Command line options. Parsed by hand to keep the dependency list small.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub log_path: Option<PathBuf>, // --log <path.csv>
+    pub confirm_quit: bool,        // --confirm-quit
+    pub compact: bool,             // --compact: start in the compact overview
+    pub smooth: Option<f64>,       // --smooth <alpha>, 0 < alpha <= 1
+    pub fill: bool,                // --fill: shade the area under chart lines
+    pub moving_average: Option<usize>, // --moving-average <points>: draw charts as a moving average
+    pub moving_average_charts: Option<Vec<ChartId>>, // --moving-average-charts cpu,ram,net,temp (all by default)
+    pub alert_rules: Vec<AlertRule>, // --alert metric:threshold[:secs] (repeatable; defaults if none)
+                                     // --alert-cpu/-ram/-temp/-disk <threshold> (immediate)
+    pub no_alerts: bool,           // --no-alerts
+    pub alert_log: Option<PathBuf>, // --alert-log <path>: append fired/cleared alerts, rotated at 1 MiB
+    pub bell: bool,                // --bell: ring the terminal bell when an alert fires
+    pub notify: bool,              // --notify: desktop notification when an alert fires
+    pub theme: Option<usize>,      // --theme neon|mono|light (index into theme::THEMES)
+    pub palette: Palette,          // --palette classic|viridis
+    pub heat_buckets: Option<[u8; 5]>,   // --heatmap-buckets 5,20,40,60,80: loads where the scale steps up
+    pub heat_colors: Option<[Color; 6]>, // --heatmap-colors c1,...,c6: names or #rrggbb, idle to saturated
+    pub serve: Option<String>,     // --serve <addr:port>: Prometheus /metrics endpoint
+    pub units: UnitMode,           // --units si|binary
+    pub temp_unit: TempUnit,       // --temp-unit c|f: also the unit of temp alert thresholds
+    pub columns: Option<Vec<Column>>, // --columns pid,name,cpu,...
+    pub user: Option<String>,      // --user <name>: only that user's processes
+    pub hide_kernel: bool,         // --hide-kernel: leave kernel threads and idle processes out of the list
+    pub hide_pseudo_fs: bool,      // --hide-pseudo-fs: leave tmpfs, squashfs, loop devices, ... out of the disk list
+    pub top: Option<usize>,        // --top <N>: list at most N processes (all by default)
+    pub chart_history: Option<usize>, // --chart-history <points>: samples kept per chart (one per 0.1s)
+    pub heatmap_width: Option<usize>, // --heatmap-width <columns>: fixed heatmap history instead of the panel width
+    pub history_file: Option<PathBuf>, // --history-file <path>: keep chart history across restarts
+    pub record: Option<PathBuf>,   // --record <path>: save the sample stream as JSON lines
+    pub replay: Option<PathBuf>,   // --replay <path>: drive the UI from a recording instead of this machine
+    pub replay_speed: Option<f64>, // --replay-speed <x>: 2 plays twice as fast
+    pub replay_loop: bool,         // --replay-loop: start over at the end instead of stopping
+    pub adaptive: bool,            // --adaptive: back off refresh while the system is idle
+    pub blocking_input: bool,      // --blocking-input: sleep until input or data instead of polling the terminal
+    pub snapshot: bool,            // --snapshot: print one sample's summary and exit
+    pub show_stats: bool,          // --show-stats: UI frame rate and samples drained per frame in the footer
+    pub no_tui: bool,              // --no-tui: run headless (with --serve, --log, --record or --alert-log)
+}
+
+impl Config {
+    pub fn from_args() -> Result<Self> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self> {
+        let mut config = Config::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--log" => {
+                    let path = args.next().context("--log requires a file path")?;
+                    config.log_path = Some(PathBuf::from(path));
+                }
+                "--confirm-quit" => config.confirm_quit = true,
+                "--compact" => config.compact = true,
+                "--smooth" => {
+                    let value = args.next().context("--smooth requires an alpha value")?;
+                    let alpha: f64 = value.parse().with_context(|| format!("invalid --smooth value: {}", value))?;
+                    if !(alpha > 0.0 && alpha <= 1.0) {
+                        bail!("--smooth alpha must be in (0, 1], got {}", alpha);
+                    }
+                    config.smooth = Some(alpha);
+                }
+                "--fill" => config.fill = true,
+                "--moving-average" => {
+                    let value = args.next().context("--moving-average requires a window size")?;
+                    let n: usize = value.parse().with_context(|| format!("invalid --moving-average value: {}", value))?;
+                    if n < 2 {
+                        bail!("--moving-average must be at least 2");
+                    }
+                    config.moving_average = Some(n);
+                }
+                "--moving-average-charts" => {
+                    let spec = args.next().context("--moving-average-charts requires a list like cpu,net")?;
+                    config.moving_average_charts = Some(ChartId::parse_list(&spec)?);
+                }
+                "--alert" => {
+                    let spec = args.next().context("--alert requires a rule like cpu:90:5")?;
+                    config.alert_rules.push(AlertRule::parse(&spec)?);
+                }
+                "--alert-cpu" | "--alert-ram" | "--alert-temp" | "--alert-disk" => {
+                    let metric = arg.trim_start_matches("--alert-");
+                    let value = args.next().with_context(|| format!("{} requires a threshold", arg))?;
+                    config.alert_rules.push(AlertRule::parse(&format!("{}:{}", metric, value))?);
+                }
+                "--no-alerts" => config.no_alerts = true,
+                "--alert-log" => {
+                    let path = args.next().context("--alert-log requires a file path")?;
+                    config.alert_log = Some(PathBuf::from(path));
+                }
+                "--bell" => config.bell = true,
+                "--notify" => config.notify = true,
+                "--theme" => {
+                    let name = args.next().context("--theme requires a name")?;
+                    let index = theme::index_of(&name).with_context(|| {
+                        let names: Vec<_> = theme::THEMES.iter().map(|t| t.name).collect();
+                        format!("unknown theme '{}' (expected one of: {})", name, names.join(", "))
+                    })?;
+                    config.theme = Some(index);
+                }
+                "--palette" => {
+                    let name = args.next().context("--palette requires classic or viridis")?;
+                    config.palette = Palette::parse(&name)?;
+                }
+                "--heatmap-buckets" => {
+                    let spec = args.next().context("--heatmap-buckets requires five loads like 5,20,40,60,80")?;
+                    config.heat_buckets = Some(theme::parse_heat_buckets(&spec)?);
+                }
+                "--heatmap-colors" => {
+                    let spec = args.next().context("--heatmap-colors requires six colors, idle to saturated")?;
+                    config.heat_colors = Some(theme::parse_heat_colors(&spec)?);
+                }
+                "--serve" => {
+                    let addr = args.next().context("--serve requires an address like 127.0.0.1:9100")?;
+                    config.serve = Some(addr);
+                }
+                "--no-tui" => config.no_tui = true,
+                "--snapshot" => config.snapshot = true,
+                "--show-stats" => config.show_stats = true,
+                "--adaptive" => config.adaptive = true,
+                "--blocking-input" => config.blocking_input = true,
+                "--units" => {
+                    let mode = args.next().context("--units requires si or binary")?;
+                    config.units = UnitMode::parse(&mode)?;
+                }
+                "--temp-unit" => {
+                    let unit = args.next().context("--temp-unit requires c or f")?;
+                    config.temp_unit = TempUnit::parse(&unit)?;
+                }
+                "--columns" => {
+                    let spec = args.next().context("--columns requires a list like pid,name,cpu,mem")?;
+                    config.columns = Some(Column::parse_list(&spec)?);
+                }
+                "--user" => {
+                    let name = args.next().context("--user requires a user name")?;
+                    config.user = Some(name);
+                }
+                "--hide-pseudo-fs" => config.hide_pseudo_fs = true,
+                "--hide-kernel" => config.hide_kernel = true,
+                "--top" => {
+                    let value = args.next().context("--top requires a process count")?;
+                    let n: usize = value.parse().with_context(|| format!("invalid --top value: {}", value))?;
+                    if n == 0 {
+                        bail!("--top must be at least 1");
+                    }
+                    config.top = Some(n);
+                }
+                "--chart-history" | "--heatmap-width" => {
+                    let value = args.next().with_context(|| format!("{} requires a count", arg))?;
+                    let n: usize = value.parse().with_context(|| format!("invalid {} value: {}", arg, value))?;
+                    if n < 2 {
+                        bail!("{} must be at least 2", arg);
+                    }
+                    if arg == "--chart-history" { config.chart_history = Some(n) } else { config.heatmap_width = Some(n) }
+                }
+                "--history-file" => {
+                    let path = args.next().context("--history-file requires a file path")?;
+                    config.history_file = Some(PathBuf::from(path));
+                }
+                "--record" => {
+                    let path = args.next().context("--record requires a file path")?;
+                    config.record = Some(PathBuf::from(path));
+                }
+                "--replay" => {
+                    let path = args.next().context("--replay requires a file path")?;
+                    config.replay = Some(PathBuf::from(path));
+                }
+                "--replay-loop" => config.replay_loop = true,
+                "--replay-speed" => {
+                    let value = args.next().context("--replay-speed requires a multiplier")?;
+                    let speed: f64 = value.parse().with_context(|| format!("invalid --replay-speed value: {}", value))?;
+                    if !(speed >= MIN_REPLAY_SPEED && speed.is_finite()) {
+                        bail!("--replay-speed must be a number of at least {}, got {}", MIN_REPLAY_SPEED, speed);
+                    }
+                    config.replay_speed = Some(speed);
+                }
+                other => bail!("unknown argument: {}", other),
+            }
+        }
+
+        // Readings stay in Celsius, so thresholds given in °F are converted once here.
+        // Done after the loop so the flag order doesn't matter.
+        for rule in config.alert_rules.iter_mut().filter(|r| r.metric == AlertMetric::Temp) {
+            rule.threshold = config.temp_unit.to_celsius(rule.threshold);
+        }
+
+        if config.snapshot
+            && (config.replay.is_some() || config.record.is_some() || config.log_path.is_some() || config.serve.is_some()
+                || config.alert_log.is_some() || config.history_file.is_some() || config.no_tui)
+        {
+            bail!("--snapshot prints one sample and exits; it can't be combined with --replay, --record, --log, --serve, --alert-log, --history-file or --no-tui");
+        }
+        if config.no_tui && config.serve.is_none() && config.log_path.is_none() && config.record.is_none() && config.alert_log.is_none() {
+            bail!("--no-tui needs --serve, --log, --record or --alert-log, otherwise there is nothing to do");
+        }
+
+        if config.replay.is_some() && (config.record.is_some() || config.no_tui || config.serve.is_some() || config.log_path.is_some()) {
+            bail!("--replay only drives the UI; it can't be combined with --record, --log, --serve or --no-tui");
+        }
+        if config.replay_loop && config.replay.is_none() {
+            bail!("--replay-loop needs --replay <file>");
+        }
+        if config.replay_speed.is_some() && config.replay.is_none() {
+            bail!("--replay-speed needs --replay <file>");
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Config> {
+        Config::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn snapshot_rejects_flags_it_would_ignore() {
+        assert!(parse(&["--snapshot", "--units", "si", "--hide-pseudo-fs"]).is_ok_and(|c| c.snapshot));
+        for extra in [&["--replay", "x.jsonl"][..], &["--no-tui", "--serve", "127.0.0.1:9100"], &["--record", "x.jsonl"], &["--log", "x.csv"]] {
+            let args: Vec<&str> = ["--snapshot"].iter().chain(extra).copied().collect();
+            assert!(parse(&args).is_err(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn replay_speed_has_a_floor() {
+        assert!(parse(&["--replay", "x.jsonl", "--replay-speed", "0.5"]).is_ok());
+        assert!(parse(&["--replay", "x.jsonl", "--replay-speed", "1e-300"]).is_err());
+    }
+}
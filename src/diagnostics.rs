@@ -0,0 +1,91 @@
+use std::time::{Duration, Instant};
+
+const WINDOW: Duration = Duration::from_secs(1);
+
+// Refresh-rate figures for the 'd' overlay, measured over one-second windows
+// so they read steadily instead of flickering frame to frame.
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    window_start: Option<Instant>,
+    frames: u32,
+    samples: u32,
+    wakeups: u32,
+    gaps: (Duration, u32),    // Sum and count of gaps between sample timestamps
+    renders: (Duration, u32), // Sum and count of frame render times
+    last_sample: Option<Instant>,
+    pub fps: f64,
+    pub samples_per_sec: f64,
+    pub wakeups_per_sec: f64,              // UI loop iterations; near 0 when idle with --blocking-input
+    pub sample_interval: Option<Duration>, // Mean gap between `SystemStats::timestamp`s
+    pub backlog: usize,                    // Events queued in the channel before the last drain
+    pub drained: usize,                    // Samples applied by the last drain, i.e. per frame
+    pub render_time: Option<Duration>,     // Mean time spent drawing a frame
+}
+
+impl Diagnostics {
+    pub fn frame(&mut self, now: Instant, render: Duration) {
+        self.frames += 1;
+        self.renders.0 += render;
+        self.renders.1 += 1;
+        self.roll(now);
+    }
+
+    pub fn sample(&mut self, timestamp: Instant, now: Instant) {
+        self.samples += 1;
+        if let Some(last) = self.last_sample {
+            self.gaps.0 += timestamp.saturating_duration_since(last);
+            self.gaps.1 += 1;
+        }
+        self.last_sample = Some(timestamp);
+        self.roll(now);
+    }
+
+    pub fn wakeup(&mut self, now: Instant) {
+        self.wakeups += 1;
+        self.roll(now);
+    }
+
+    fn roll(&mut self, now: Instant) {
+        let start = *self.window_start.get_or_insert(now);
+        let elapsed = now.saturating_duration_since(start);
+        if elapsed < WINDOW {
+            return;
+        }
+        let secs = elapsed.as_secs_f64();
+        self.fps = self.frames as f64 / secs;
+        self.samples_per_sec = self.samples as f64 / secs;
+        self.wakeups_per_sec = self.wakeups as f64 / secs;
+        self.sample_interval = (self.gaps.1 > 0).then(|| self.gaps.0 / self.gaps.1);
+        self.render_time = (self.renders.1 > 0).then(|| self.renders.0 / self.renders.1);
+        (self.frames, self.samples, self.wakeups, self.gaps, self.renders) = (0, 0, 0, (Duration::ZERO, 0), (Duration::ZERO, 0));
+        self.window_start = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rates_update_once_per_window() {
+        let start = Instant::now();
+        let mut diag = Diagnostics::default();
+        let render = Duration::from_millis(2);
+        diag.frame(start, render);
+        for i in 0..10 {
+            let t = start + Duration::from_millis(100 * i);
+            diag.sample(t, t);
+            diag.frame(t, render);
+            diag.wakeup(t);
+        }
+        assert_eq!(diag.fps, 0.0); // Window not over yet
+
+        let end = start + Duration::from_secs(1);
+        diag.frame(end, render);
+        assert_eq!(diag.fps, 12.0);
+        assert_eq!(diag.render_time, Some(render));
+        assert_eq!(diag.samples_per_sec, 10.0);
+        assert_eq!(diag.wakeups_per_sec, 10.0);
+        assert_eq!(diag.sample_interval, Some(Duration::from_millis(100)));
+    }
+}
@@ -0,0 +1,80 @@
+// Drive temperature picked out of the hwmon sensors. Best-effort: sensor labels
+// only name the driver ("nvme Composite", "drivetemp temp1"), so a disk is matched
+// when exactly one sensor of its family could belong to it.
+
+const DEFAULT_WARN_C: f32 = 60.0; // Used when the sensor reports no critical threshold
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DiskHealth {
+    pub temp: f32,
+    pub warning: bool, // At or past the sensor's critical point (or DEFAULT_WARN_C)
+}
+
+// A temperature reading as the monitor sees it: (label, °C, critical °C)
+pub type Sensor = (String, f32, Option<f32>);
+
+// "nvme0n1p2" -> ("nvme", "nvme0"), "sda1" -> ("drivetemp", "sda"). Mapper and loop
+// devices have no sensor of their own.
+fn family(device: &str) -> Option<(&'static str, &str)> {
+    if let Some(rest) = device.strip_prefix("nvme") {
+        let end = rest.find('n').map_or(device.len(), |i| "nvme".len() + i);
+        Some(("nvme", &device[..end]))
+    } else if device.starts_with("sd") || device.starts_with("hd") {
+        Some(("drivetemp", device.trim_end_matches(|c: char| c.is_ascii_digit())))
+    } else {
+        None
+    }
+}
+
+// The sensor for a kernel device name, if one can be told apart. A controller name
+// in the label wins; otherwise NVMe's "Composite" reading stands for the drive, and
+// two drives of the same family with identical labels are left unmatched.
+pub fn sensor_for(device: &str, sensors: &[Sensor]) -> Option<DiskHealth> {
+    let (family, controller) = family(device)?;
+    let candidates: Vec<&Sensor> = sensors.iter()
+        .filter(|(label, _, _)| label.to_lowercase().split_whitespace().next() == Some(family))
+        .collect();
+    let named = candidates.iter().find(|(label, _, _)| label.to_lowercase().split_whitespace().any(|w| w == controller));
+    let picked = match named {
+        Some(sensor) => *sensor,
+        None => {
+            let composite: Vec<&Sensor> = candidates.iter().copied().filter(|(label, _, _)| label.to_lowercase().contains("composite")).collect();
+            match (composite.as_slice(), candidates.as_slice()) {
+                ([only], _) | ([], [only]) => *only,
+                _ => return None,
+            }
+        }
+    };
+    let (_, temp, critical) = picked;
+    Some(DiskHealth { temp: *temp, warning: *temp >= critical.unwrap_or(DEFAULT_WARN_C) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sensor(label: &str, temp: f32, critical: Option<f32>) -> Sensor {
+        (label.to_string(), temp, critical)
+    }
+
+    #[test]
+    fn matches_disks_to_sensors_by_family() {
+        let sensors = vec![
+            sensor("coretemp Package id 0", 55.0, Some(100.0)),
+            sensor("nvme Composite", 41.0, Some(84.0)),
+            sensor("nvme Sensor 1", 39.0, None),
+            sensor("drivetemp temp1", 62.0, None),
+        ];
+        assert_eq!(sensor_for("nvme0n1p2", &sensors), Some(DiskHealth { temp: 41.0, warning: false }));
+        assert_eq!(sensor_for("sdb1", &sensors), Some(DiskHealth { temp: 62.0, warning: true }));
+        assert_eq!(sensor_for("dm-0", &sensors), None);
+    }
+
+    #[test]
+    fn leaves_ambiguous_drives_unmatched() {
+        let sensors = vec![sensor("nvme Composite", 41.0, None), sensor("nvme Composite", 48.0, None)];
+        assert_eq!(sensor_for("nvme1n1", &sensors), None);
+        let named = vec![sensor("nvme nvme0 Composite", 41.0, None), sensor("nvme nvme1 Composite", 48.0, None)];
+        assert_eq!(sensor_for("nvme1n1p1", &named).map(|h| h.temp), Some(48.0));
+    }
+}
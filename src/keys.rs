@@ -0,0 +1,121 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Down,
+    Up,
+    Top,
+    Bottom,
+    PageDown,
+    PageUp,
+    HalfPageDown,
+    HalfPageUp,
+    ScrollPageDown,
+    ScrollPageUp,
+    CycleSort,
+    ReverseSort,
+    ToggleTree,
+    ToggleGroup,
+    ToggleUserGroup,
+    FilterByOwner,
+    ToggleKernel,
+    TogglePin,
+    ToggleIrixMode,
+    ToggleSortLock,
+    ToggleSmoothing,
+    ToggleMovingAverage,
+    ToggleChartFill,
+    ScrollDisksDown,
+    ScrollDisksUp,
+    ToggleCompact,
+    ToggleCpuBand,
+    ToggleCpuBreakdown,
+    CycleTempView,
+    ToggleTempUnit,
+    ResetSummaries,
+    AddMarker,
+    ClearMarkers,
+    ZoomIn,
+    ZoomOut,
+    CycleTheme,
+    CycleAccent,
+    ToggleDetail,
+    ExportReport,
+    OpenKillMenu,
+    SuspendProcess,
+    ResumeProcess,
+    ToggleSockets,
+    ToggleStorage,
+    CycleFocus,
+    ToggleHelp,
+    ToggleDiagnostics,
+    CloseOverlay,
+}
+
+pub struct KeyBinding {
+    pub keys: &'static [KeyCode],
+    pub ctrl: bool,          // Only matches with Ctrl held (and plain keys only without it)
+    pub label: &'static str, // How the keys are shown in the help overlay
+    pub action: Action,
+    pub description: &'static str,
+}
+
+// Single source of truth: `App::on_key_code` dispatches through this table and the
+// help overlay renders it, so a binding can't exist without being documented.
+pub const KEY_BINDINGS: &[KeyBinding] = &[
+    KeyBinding { keys: &[KeyCode::Char('q'), KeyCode::Char('Q')], ctrl: false, label: "q", action: Action::Quit, description: "Quit" },
+    KeyBinding { keys: &[KeyCode::Tab], ctrl: false, label: "Tab", action: Action::CycleFocus, description: "Switch focus between the process and disk lists" },
+    KeyBinding { keys: &[KeyCode::Down, KeyCode::Char('j')], ctrl: false, label: "j / ↓", action: Action::Down, description: "Select next row in the focused list" },
+    KeyBinding { keys: &[KeyCode::Up, KeyCode::Char('k')], ctrl: false, label: "k / ↑", action: Action::Up, description: "Select previous row in the focused list" },
+    KeyBinding { keys: &[KeyCode::Char('g'), KeyCode::Home], ctrl: false, label: "g / Home", action: Action::Top, description: "Jump to the first row" },
+    KeyBinding { keys: &[KeyCode::Char('G'), KeyCode::End], ctrl: false, label: "G / End", action: Action::Bottom, description: "Jump to the last row" },
+    KeyBinding { keys: &[KeyCode::PageDown], ctrl: false, label: "PgDn", action: Action::PageDown, description: "Scroll down a page" },
+    KeyBinding { keys: &[KeyCode::PageUp], ctrl: false, label: "PgUp", action: Action::PageUp, description: "Scroll up a page" },
+    KeyBinding { keys: &[KeyCode::Char('d')], ctrl: true, label: "Ctrl-d", action: Action::HalfPageDown, description: "Scroll down half a page" },
+    KeyBinding { keys: &[KeyCode::Char('u')], ctrl: true, label: "Ctrl-u", action: Action::HalfPageUp, description: "Scroll up half a page" },
+    KeyBinding { keys: &[KeyCode::Char('e')], ctrl: true, label: "Ctrl-e", action: Action::ScrollPageDown, description: "Scroll the narrow layout down a panel" },
+    KeyBinding { keys: &[KeyCode::Char('y')], ctrl: true, label: "Ctrl-y", action: Action::ScrollPageUp, description: "Scroll the narrow layout up a panel" },
+    KeyBinding { keys: &[KeyCode::Char('s')], ctrl: false, label: "s", action: Action::CycleSort, description: "Cycle sort column (CPU, MEM, DISK, PID, NAME)" },
+    KeyBinding { keys: &[KeyCode::Char('S')], ctrl: false, label: "S", action: Action::ReverseSort, description: "Reverse sort direction" },
+    KeyBinding { keys: &[KeyCode::Char('l')], ctrl: false, label: "l", action: Action::ToggleSortLock, description: "Lock row order (values keep updating)" },
+    KeyBinding { keys: &[KeyCode::Char('t')], ctrl: false, label: "t", action: Action::ToggleTree, description: "Toggle process tree view" },
+    KeyBinding { keys: &[KeyCode::Char('n')], ctrl: false, label: "n", action: Action::ToggleGroup, description: "Toggle grouping processes by name" },
+    KeyBinding { keys: &[KeyCode::Char('u')], ctrl: false, label: "u", action: Action::ToggleUserGroup, description: "Toggle grouping processes by user" },
+    KeyBinding { keys: &[KeyCode::Char('o')], ctrl: false, label: "o", action: Action::FilterByOwner, description: "Show only the selected process's owner (again to clear)" },
+    KeyBinding { keys: &[KeyCode::Char('K')], ctrl: false, label: "K", action: Action::ToggleKernel, description: "Hide kernel threads and idle processes" },
+    KeyBinding { keys: &[KeyCode::Char('P')], ctrl: false, label: "P", action: Action::TogglePin, description: "Pin the selected process's name to the top of the list (again to unpin)" },
+    KeyBinding { keys: &[KeyCode::Char('I')], ctrl: false, label: "I", action: Action::ToggleIrixMode, description: "Toggle process CPU% per core (Irix) / of all cores (Solaris)" },
+    KeyBinding { keys: &[KeyCode::Char('m')], ctrl: false, label: "m", action: Action::ToggleSmoothing, description: "Toggle chart smoothing (EMA)" },
+    KeyBinding { keys: &[KeyCode::Char('M')], ctrl: false, label: "M", action: Action::ToggleMovingAverage, description: "Toggle moving average on the charts (--moving-average)" },
+    KeyBinding { keys: &[KeyCode::Char('F')], ctrl: false, label: "F", action: Action::ToggleChartFill, description: "Toggle filled chart areas" },
+    KeyBinding { keys: &[KeyCode::Char('b')], ctrl: false, label: "b", action: Action::ToggleCpuBand, description: "Toggle CPU min/max band" },
+    KeyBinding { keys: &[KeyCode::Char('B')], ctrl: false, label: "B", action: Action::ToggleCpuBreakdown, description: "Toggle stacked CPU chart (user, system, iowait, steal)" },
+    KeyBinding { keys: &[KeyCode::Char('e')], ctrl: false, label: "e", action: Action::CycleTempView, description: "Cycle temperature chart (max, each sensor, hottest 3)" },
+    KeyBinding { keys: &[KeyCode::Char('f')], ctrl: false, label: "f", action: Action::ToggleTempUnit, description: "Show temperatures in °C / °F" },
+    KeyBinding { keys: &[KeyCode::Char(']')], ctrl: false, label: "]", action: Action::ScrollDisksDown, description: "Scroll the disk list down" },
+    KeyBinding { keys: &[KeyCode::Char('[')], ctrl: false, label: "[", action: Action::ScrollDisksUp, description: "Scroll the disk list up" },
+    KeyBinding { keys: &[KeyCode::Char('r')], ctrl: false, label: "r", action: Action::ResetSummaries, description: "Reset since-launch min/avg/max" },
+    KeyBinding { keys: &[KeyCode::Char('a')], ctrl: false, label: "a", action: Action::AddMarker, description: "Mark now on the charts" },
+    KeyBinding { keys: &[KeyCode::Char('A')], ctrl: false, label: "A", action: Action::ClearMarkers, description: "Clear chart markers" },
+    KeyBinding { keys: &[KeyCode::Char('+'), KeyCode::Char('=')], ctrl: false, label: "+", action: Action::ZoomIn, description: "Zoom charts in (shorter time span)" },
+    KeyBinding { keys: &[KeyCode::Char('-')], ctrl: false, label: "-", action: Action::ZoomOut, description: "Zoom charts out (longer time span)" },
+    KeyBinding { keys: &[KeyCode::Char('v')], ctrl: false, label: "v", action: Action::ToggleCompact, description: "Toggle compact overview" },
+    KeyBinding { keys: &[KeyCode::Char('c')], ctrl: false, label: "c", action: Action::CycleTheme, description: "Cycle color theme (neon, mono, light)" },
+    KeyBinding { keys: &[KeyCode::Char('T')], ctrl: false, label: "T", action: Action::CycleAccent, description: "Cycle accent color" },
+    KeyBinding { keys: &[KeyCode::Char('p')], ctrl: false, label: "p", action: Action::ExportReport, description: "Save a text report of the current state" },
+    KeyBinding { keys: &[KeyCode::Char('x')], ctrl: false, label: "x", action: Action::OpenKillMenu, description: "Send a signal to the selected process" },
+    KeyBinding { keys: &[KeyCode::Char('z')], ctrl: false, label: "z", action: Action::SuspendProcess, description: "Suspend the selected process (SIGSTOP)" },
+    KeyBinding { keys: &[KeyCode::Char('Z')], ctrl: false, label: "Z", action: Action::ResumeProcess, description: "Resume the selected process (SIGCONT)" },
+    KeyBinding { keys: &[KeyCode::Char('D')], ctrl: false, label: "D", action: Action::ToggleStorage, description: "Toggle the storage table (every disk; 's' sorts it while focused)" },
+    KeyBinding { keys: &[KeyCode::Char('N')], ctrl: false, label: "N", action: Action::ToggleSockets, description: "Show open network connections" },
+    KeyBinding { keys: &[KeyCode::Enter], ctrl: false, label: "Enter", action: Action::ToggleDetail, description: "Show details for the selected process" },
+    KeyBinding { keys: &[KeyCode::Char('d')], ctrl: false, label: "d", action: Action::ToggleDiagnostics, description: "Toggle frame/sample rate diagnostics" },
+    KeyBinding { keys: &[KeyCode::Char('?')], ctrl: false, label: "?", action: Action::ToggleHelp, description: "Toggle this help" },
+    KeyBinding { keys: &[KeyCode::Esc], ctrl: false, label: "Esc", action: Action::CloseOverlay, description: "Close overlay" },
+];
+
+pub fn action_for(code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+    let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+    KEY_BINDINGS.iter().find(|b| b.ctrl == ctrl && b.keys.contains(&code)).map(|b| b.action)
+}
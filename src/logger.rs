@@ -0,0 +1,57 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use crate::monitor::SystemStats;
+
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+// Appends one CSV row per sample. Lives on the monitor thread so it keeps
+// logging regardless of what the UI is doing.
+pub struct CsvLogger {
+    writer: BufWriter<File>,
+    start: Instant,
+    last_flush: Instant,
+}
+
+impl CsvLogger {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(
+            writer,
+            "timestamp,elapsed_s,cpu_total,ram_used,ram_total,swap_used,swap_total,rx_speed,tx_speed,rx_bytes,tx_bytes,max_temp"
+        )?;
+        writer.flush()?;
+
+        let now = Instant::now();
+        Ok(Self { writer, start: now, last_flush: now })
+    }
+
+    pub fn write(&mut self, stats: &SystemStats) -> io::Result<()> {
+        let max_temp = stats.temperatures.iter().map(|t| t.celsius).fold(0.0_f32, f32::max);
+        let elapsed = stats.timestamp.saturating_duration_since(self.start).as_secs_f64();
+
+        writeln!(
+            self.writer,
+            "{},{:.3},{:.2},{},{},{},{},{},{},{},{},{:.1}",
+            chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f"),
+            elapsed,
+            stats.total_cpu_usage,
+            stats.ram_used,
+            stats.ram_total,
+            stats.swap_used,
+            stats.swap_total,
+            stats.rx_speed,
+            stats.tx_speed,
+            stats.rx_bytes,
+            stats.tx_bytes,
+            max_temp,
+        )?;
+
+        if self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.writer.flush()?;
+            self.last_flush = Instant::now();
+        }
+        Ok(())
+    }
+}
@@ -1,92 +1,396 @@
+mod alert_log;
+mod alerts;
 mod app;
+mod battery;
+mod config;
+mod diagnostics;
+mod disk_health;
+mod keys;
+mod logger;
+mod metrics;
 mod monitor;
+mod notify;
+mod recording;
+mod report;
+mod sockets;
+mod state;
+mod theme;
+mod topology;
 mod ui;
+mod units;
 
+use alert_log::AlertLog;
+use alerts::{AlertEngine, AlertRule};
 use app::App;
-use monitor::{Monitor, MonitorEvent};
+use config::Config;
+use logger::CsvLogger;
+use metrics::SharedStats;
+use monitor::{Monitor, MonitorCommand, MonitorEvent};
+use recording::Recorder;
+use state::ViewState;
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::{io, time::{Duration, Instant}};
-use crossbeam_channel::unbounded;
+use std::{io::{self, Write}, path::Path, time::{Duration, Instant}};
+use crossbeam_channel::{unbounded, Receiver, Select, TryRecvError};
+use anyhow::Context;
+
+const HISTORY_SAVE_INTERVAL: Duration = Duration::from_secs(60); // --history-file snapshots
 
 fn main() -> Result<()> {
+    // 0. Parse CLI & open optional outputs before touching the terminal
+    let config = Config::from_args()?;
+    if config.snapshot {
+        let (tx, _) = unbounded();
+        let stats = Monitor::new(tx).with_hide_pseudo_fs(config.hide_pseudo_fs).snapshot();
+        print!("{}", report::snapshot(&stats, config.units));
+        return Ok(());
+    }
+    let logger = match &config.log_path {
+        Some(path) => Some(CsvLogger::create(path).with_context(|| format!("failed to open log file {}", path.display()))?),
+        None => None,
+    };
+    let alert_log = match &config.alert_log {
+        Some(path) => Some(AlertLog::open(path).with_context(|| format!("failed to open alert log {}", path.display()))?),
+        None => None,
+    };
+    let recorder = match &config.record {
+        Some(path) => Some(Recorder::create(path).with_context(|| format!("failed to open recording file {}", path.display()))?),
+        None => None,
+    };
+    let shared = match &config.serve {
+        Some(addr) => {
+            let shared = SharedStats::default();
+            metrics::serve(addr, shared.clone())?;
+            Some(shared)
+        }
+        None => None,
+    };
+
+    let (tx, rx) = unbounded();
+    if let Some(path) = &config.replay {
+        recording::replay(path, tx.clone(), config.replay_loop, config.replay_speed.unwrap_or(1.0))?;
+    }
+    let (command_tx, command_rx) = unbounded();
+    let mut monitor = Monitor::new(tx)
+        .with_adaptive(config.adaptive)
+        .with_hide_pseudo_fs(config.hide_pseudo_fs)
+        .with_commands(command_rx);
+    if let Some(logger) = logger {
+        monitor = monitor.with_logger(logger);
+    }
+    if let Some(recorder) = recorder {
+        monitor = monitor.with_recorder(recorder);
+    }
+    if let Some(shared) = shared {
+        monitor = monitor.with_shared_stats(shared);
+    }
+
+    if config.no_tui {
+        monitor.run();
+        run_headless(rx, alert_engine(&config), alert_log);
+        return Ok(());
+    }
+
     // 1. Setup Terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
+    let guard = TerminalGuard::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
 
     // 2. Setup App & Monitor
-    // History length for sparklines (e.g., last 200 ticks)
-    let app = App::new(200); 
-    let (tx, rx) = unbounded();
-    
-    // Start Monitor Thread
-    let monitor = Monitor::new(tx);
-    monitor.run();
+    // Chart history defaults to the widest zoom level
+    let mut app = App::new(config.chart_history.unwrap_or(app::CHART_HISTORY_LEN));
+    app.heatmap_fixed_width = config.heatmap_width;
+    app.color_support = theme::ColorSupport::detect();
+    app.palette = config.palette;
+    app.heat_buckets = config.heat_buckets;
+    app.heat_colors = config.heat_colors;
+    app.core_types = topology::core_types();
+    ViewState::load().apply(&mut app);
+    app.commands = Some(command_tx);
+    app.watch_staleness = config.replay.is_none();
+    app.confirm_quit = config.confirm_quit;
+    app.units = config.units;
+    app.temp_unit = config.temp_unit;
+    app.user_filter = config.user.clone();
+    app.hide_kernel = config.hide_kernel;
+    app.process_limit = config.top;
+    app.show_stats = config.show_stats;
+    if let Some(columns) = &config.columns {
+        app.columns = columns.clone();
+    }
+    if config.compact {
+        app.display_mode = app::DisplayMode::Compact;
+    }
+    app.alert_bell = config.bell;
+    if config.notify {
+        app.notifier = Some(notify::Notifier::new());
+    }
+    app.alerts = alert_engine(&config);
+    app.alert_log = alert_log;
+    if let Some(index) = config.theme {
+        app.theme_index = index;
+    }
+    if let Some(alpha) = config.smooth {
+        app.smoothing_alpha = alpha;
+        app.smoothing_enabled = true;
+    }
+    if config.fill {
+        app.chart_fill = true;
+    }
+    if let Some(charts) = &config.moving_average_charts {
+        app.moving_average_charts = charts.clone();
+    }
+    if config.moving_average.is_some() || config.moving_average_charts.is_some() {
+        app.moving_average_window = config.moving_average.unwrap_or(app::DEFAULT_MOVING_AVERAGE);
+        app.moving_average_enabled = true;
+    }
+
+    if let Some(path) = &config.history_file
+        && let Err(err) = app.load_state(path)
+    {
+        app.set_footer_message(format!("HISTORY NOT RESTORED: {}", err));
+    }
+
+    // Start Monitor Thread (a replay already feeds the channel in its place)
+    let monitor_thread = config.replay.is_none().then(|| monitor.run());
 
     // 3. Run Event Loop
-    let res = run_app(&mut terminal, app, rx);
+    let res = run_app(&mut terminal, &mut app, rx, (config.adaptive, config.blocking_input), config.history_file.as_deref());
+
+    // Stop sampling before handing the terminal back
+    if let Some(handle) = monitor_thread {
+        if let Some(commands) = &app.commands {
+            let _ = commands.send(MonitorCommand::Shutdown);
+        }
+        let _ = handle.join();
+    }
 
     // 4. Restore Terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    drop(guard);
 
     if let Err(err) = res {
         println!("{:?}", err);
     }
+    if let Err(err) = ViewState::capture(&app).save() {
+        eprintln!("failed to save view state: {:?}", err);
+    }
+    if let Some(path) = &config.history_file
+        && let Err(err) = app.save_state(path)
+    {
+        eprintln!("failed to save history to {}: {:?}", path.display(), err);
+    }
 
     Ok(())
 }
 
-fn run_app<B: ratatui::backend::Backend>(
+// Raw mode and the alternate screen, undone on drop so an early `?` return or a panic
+// on the UI thread doesn't leave the shell garbled
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        enable_raw_mode()?;
+        let guard = Self;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+
+        // The default hook prints the message straight away, i.e. into the alternate
+        // screen that the guard's drop is about to discard. Restore first so it stays
+        // readable. A monitor thread panic leaves the UI running (it shows a banner).
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if std::thread::current().name() == Some("main") {
+                restore_terminal();
+            }
+            default_hook(info);
+        }));
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, crossterm::cursor::Show);
+}
+
+fn alert_engine(config: &Config) -> AlertEngine {
+    if config.no_alerts {
+        AlertEngine::new(Vec::new())
+    } else if !config.alert_rules.is_empty() {
+        AlertEngine::new(config.alert_rules.clone())
+    } else {
+        AlertEngine::new(AlertRule::defaults())
+    }
+}
+
+// Without a UI the samples only feed the logger, recorder, alert log and metrics endpoint;
+// keep draining the channel so it doesn't grow without bound. Runs until the process is killed.
+fn run_headless(rx: crossbeam_channel::Receiver<MonitorEvent>, mut alerts: AlertEngine, mut alert_log: Option<AlertLog>) {
+    while let Ok(msg) = rx.recv() {
+        match msg {
+            MonitorEvent::Stats(stats) if alert_log.is_some() => {
+                let fired = alerts.evaluate(&stats, Instant::now());
+                if let Some(log) = &mut alert_log
+                    && let Err(err) = fired.iter().try_for_each(|a| log.fired(a)).and_then(|_| alerts.cleared().try_for_each(|a| log.cleared(&a)))
+                {
+                    eprintln!("alert log disabled: {}", err);
+                    alert_log = None;
+                }
+            }
+            MonitorEvent::LogError(err) => eprintln!("CSV logging disabled: {}", err),
+            MonitorEvent::RecordError(err) => eprintln!("recording stopped: {}", err),
+            _ => {}
+        }
+    }
+}
+
+// Blocks in `event::read` on its own thread so the UI loop can sleep on channels
+// instead of polling the terminal. Stops after the first read error.
+fn spawn_input_reader() -> Receiver<io::Result<Event>> {
+    let (tx, rx) = unbounded();
+    std::thread::spawn(move || loop {
+        let event = event::read();
+        let failed = event.is_err();
+        if tx.send(event).is_err() || failed {
+            return;
+        }
+    });
+    rx
+}
+
+fn on_input(app: &mut App, event: Event) {
+    match event {
+        Event::Key(key) => app.on_key(key),
+        Event::Mouse(mouse) => app.on_mouse(mouse),
+        _ => {}
+    }
+}
+
+// `blocking` (--blocking-input) swaps the terminal poll for a wait on input and monitor
+// data together; the loop then only wakes for one of those, or to redraw
+fn run_app<B: ratatui::backend::Backend + Write>(
     terminal: &mut Terminal<B>,
-    mut app: App,
-    rx: crossbeam_channel::Receiver<MonitorEvent>,
+    app: &mut App,
+    rx: Receiver<MonitorEvent>,
+    (adaptive, blocking): (bool, bool),
+    history_file: Option<&Path>,
 ) -> io::Result<()> {
     let tick_rate = Duration::from_millis(30); // ~30 FPS UI refresh rate
     let mut last_tick = Instant::now();
+    let mut last_history_save = Instant::now();
+
+    // Adaptive mode only redraws when something visible changed: input, a new chart
+    // point, or the idle refresh (keeps clocks and alert blinking alive)
+    let idle_redraw = Duration::from_millis(500);
+    let mut dirty = true;
+    let mut last_draw = Instant::now();
+    let mut drawn_tick = app.chart_tick_count;
+    let input = blocking.then(spawn_input_reader);
 
     loop {
-        // 1. Draw UI
-        terminal.draw(|f| ui::draw(f, &app))?;
+        // 1. Draw UI (blocking mode: only on changes, like adaptive, and at most once per tick)
+        let changed = dirty || app.chart_tick_count != drawn_tick || last_draw.elapsed() >= idle_redraw;
+        let due = if blocking { changed && last_draw.elapsed() >= tick_rate } else { !adaptive || changed };
+        if due {
+            let render_start = Instant::now();
+            terminal.draw(|f| ui::draw(f, app))?;
+            app.diagnostics.frame(Instant::now(), render_start.elapsed());
+            dirty = false;
+            last_draw = Instant::now();
+            drawn_tick = app.chart_tick_count;
+        }
+        if std::mem::take(&mut app.bell_pending) {
+            terminal.backend_mut().write_all(b"\x07")?;
+            Write::flush(terminal.backend_mut())?;
+        }
 
         // 2. Handle Input (with timeout for tick rate)
-        let timeout = tick_rate
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_secs(0));
-            
-        if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                app.on_key_code(key.code);
+        if let Some(input) = &input {
+            // Sleep until input or data arrives, a pending redraw is allowed, or the idle redraw is due
+            let changed = dirty || app.chart_tick_count != drawn_tick;
+            let wake = if changed { tick_rate } else { idle_redraw };
+            let mut select = Select::new();
+            select.recv(input);
+            if !app.monitor_disconnected {
+                select.recv(&rx); // A disconnected channel is always "ready"
+            }
+            let _ = select.ready_timeout(wake.saturating_sub(last_draw.elapsed()));
+            for event in input.try_iter() {
+                on_input(app, event?);
+                dirty = true;
+            }
+        } else {
+            let timeout = tick_rate
+                .checked_sub(last_tick.elapsed())
+                .unwrap_or_else(|| Duration::from_secs(0));
+
+            if crossterm::event::poll(timeout)? {
+                on_input(app, event::read()?);
+                dirty = true;
             }
         }
 
         if app.should_quit {
             return Ok(());
         }
+        app.diagnostics.wakeup(Instant::now());
 
         // 3. Process All Pending Data Events
-        while let Ok(msg) = rx.try_recv() {
+        app.diagnostics.backlog = rx.len();
+        let mut drained = 0;
+        loop {
+            let msg = match rx.try_recv() {
+                Ok(msg) => msg,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    // The monitor thread panicked or exited; keep the last data up but say so
+                    dirty |= !app.monitor_disconnected;
+                    app.monitor_disconnected = true;
+                    break;
+                }
+            };
             match msg {
                 MonitorEvent::Stats(stats) => {
-                    app.on_tick(stats);
+                    app.diagnostics.sample(stats.timestamp, Instant::now());
+                    app.on_tick(*stats);
+                    drained += 1;
+                }
+                MonitorEvent::LogError(err) => {
+                    app.set_footer_message(format!("CSV LOGGING DISABLED: {}", err));
+                    dirty = true;
+                }
+                MonitorEvent::RecordError(err) => {
+                    app.set_footer_message(format!("RECORDING STOPPED: {}", err));
+                    dirty = true;
+                }
+                MonitorEvent::CommandResult(msg) | MonitorEvent::ReplayEnded(msg) => {
+                    app.set_footer_message(msg);
+                    dirty = true;
                 }
             }
         }
-        
+        app.diagnostics.drained = drained;
+
+        if let Some(path) = history_file
+            && last_history_save.elapsed() >= HISTORY_SAVE_INTERVAL
+        {
+            if let Err(err) = app.save_state(path) {
+                app.set_footer_message(format!("HISTORY NOT SAVED: {}", err));
+            }
+            last_history_save = Instant::now();
+        }
+
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
         }
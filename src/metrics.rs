@@ -0,0 +1,83 @@
+use std::fmt::Write as _;
+use std::sync::Arc;
+use parking_lot::Mutex;
+use crate::monitor::SystemStats;
+
+// Latest sample, published by the monitor thread and read by the HTTP server
+pub type SharedStats = Arc<Mutex<Option<SystemStats>>>;
+
+// Serves `/metrics` in Prometheus text exposition format on a background thread
+#[cfg(feature = "serve")]
+pub fn serve(addr: &str, shared: SharedStats) -> anyhow::Result<()> {
+    let server = tiny_http::Server::http(addr)
+        .map_err(|e| anyhow::anyhow!("failed to listen on {}: {}", addr, e))?;
+
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let path = request.url().split('?').next().unwrap_or("");
+            let response = if path != "/metrics" {
+                tiny_http::Response::from_string("not found\n").with_status_code(404)
+            } else {
+                match shared.lock().as_ref() {
+                    Some(stats) => tiny_http::Response::from_string(render(stats)).with_header(
+                        tiny_http::Header::from_bytes("Content-Type", "text/plain; version=0.0.4").expect("static header"),
+                    ),
+                    None => tiny_http::Response::from_string("no sample yet\n").with_status_code(503),
+                }
+            };
+            let _ = request.respond(response);
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(feature = "serve"))]
+pub fn serve(_addr: &str, _shared: SharedStats) -> anyhow::Result<()> {
+    anyhow::bail!("--serve needs the `serve` feature, which this build was compiled without")
+}
+
+#[cfg_attr(not(feature = "serve"), allow(dead_code))]
+pub fn render(stats: &SystemStats) -> String {
+    let mut out = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, f64)]| {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        for (labels, value) in samples {
+            let _ = writeln!(out, "{}{} {}", name, labels, value);
+        }
+    };
+
+    metric("cpu_usage", "gauge", "Total CPU usage in percent.", &[(String::new(), stats.total_cpu_usage as f64)]);
+    let cores: Vec<_> = stats.cpu_ids.iter().zip(stats.cpu_usage.iter())
+        .map(|(id, v)| (format!("{{core=\"{}\"}}", id), *v as f64))
+        .collect();
+    metric("cpu_core_usage", "gauge", "Per-core CPU usage in percent.", &cores);
+    metric("memory_used_bytes", "gauge", "Used RAM in bytes.", &[(String::new(), stats.ram_used as f64)]);
+    metric("memory_available_bytes", "gauge", "RAM available for new allocations (free plus reclaimable cache).", &[(String::new(), stats.ram_available as f64)]);
+    metric("memory_cached_bytes", "gauge", "Page cache and buffers in bytes (Linux only).", &[(String::new(), (stats.ram_buffers + stats.ram_cached) as f64)]);
+    metric("memory_total_bytes", "gauge", "Total RAM in bytes.", &[(String::new(), stats.ram_total as f64)]);
+    metric("swap_used_bytes", "gauge", "Used swap in bytes.", &[(String::new(), stats.swap_used as f64)]);
+    metric("swap_in_pages_per_second", "gauge", "Pages swapped in per second (Linux only).", &[(String::new(), stats.swap_in_rate)]);
+    metric("swap_out_pages_per_second", "gauge", "Pages swapped out per second (Linux only).", &[(String::new(), stats.swap_out_rate)]);
+    metric("network_rx_bytes", "counter", "Bytes received on all interfaces.", &[(String::new(), stats.rx_bytes as f64)]);
+    metric("network_tx_bytes", "counter", "Bytes transmitted on all interfaces.", &[(String::new(), stats.tx_bytes as f64)]);
+    let busy: Vec<_> = stats.disk_busy.iter()
+        .map(|(name, pct)| (format!("{{disk=\"{}\"}}", escape_label(name)), *pct as f64))
+        .collect();
+    metric("disk_busy_percent", "gauge", "Share of time each disk had I/O in flight (Linux only).", &busy);
+    if let Some(c) = stats.connections {
+        let conns = [("established", c.established), ("time_wait", c.time_wait), ("listen", c.listen), ("other", c.other)]
+            .map(|(state, n)| (format!("{{state=\"{}\"}}", state), n as f64));
+        metric("tcp_connections", "gauge", "TCP sockets by state, IPv4 and IPv6 (Linux only).", &conns);
+    }
+    let temps: Vec<_> = stats.temperatures.iter()
+        .map(|t| (format!("{{sensor=\"{}\"}}", escape_label(&t.name)), t.celsius as f64))
+        .collect();
+    metric("temperature_celsius", "gauge", "Sensor temperature in degrees Celsius.", &temps);
+    out
+}
+
+#[cfg_attr(not(feature = "serve"), allow(dead_code))]
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
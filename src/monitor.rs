@@ -1,48 +1,480 @@
-use std::thread;
+use std::collections::{HashMap, HashSet};
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
-use crossbeam_channel::Sender;
-use sysinfo::{System, Networks, Disks, Components};
+use crossbeam_channel::{Receiver, Sender};
+use sysinfo::{System, Networks, Disks, Components, ProcessRefreshKind, UpdateKind, Users};
+use crate::battery::{BatteryInfo, BatteryReader};
+use crate::disk_health::{self, DiskHealth};
+use crate::logger::CsvLogger;
+use crate::recording::Recorder;
+use crate::metrics::SharedStats;
+use crate::sockets::{self, ConnCounts, Connection};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ProcessInfo {
     pub pid: u32,
     pub name: String,
     pub cpu: f32,
     pub mem: u64,
+    pub parent_pid: Option<u32>,
+    pub disk_read: u64,  // B/s over the last process refresh (0 where unsupported)
+    pub disk_write: u64,
+    // Detail fields (shown in the process overlay)
+    pub cmd: String,
+    pub exe: Option<String>,
+    pub user: Option<String>,
+    pub start_time: u64, // Seconds since epoch
+    pub threads: Option<usize>,
+    pub disk_read_total: u64,
+    pub disk_write_total: u64,
+    pub status: String,
+    #[serde(default)]
+    pub cwd: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+impl ProcessInfo {
+    // sysinfo's label for ProcessStatus::Stop (SIGSTOP, Ctrl-Z)
+    pub fn is_stopped(&self) -> bool {
+        self.status == "Stopped"
+    }
+
+    // Linux kernel threads: kthreadd (PID 2) and its children. They have no executable
+    // or command line for anyone, root included.
+    pub fn is_kernel_thread(&self) -> bool {
+        cfg!(target_os = "linux") && (self.pid == 2 || self.parent_pid == Some(2))
+    }
+
+    // What --hide-kernel / 'K' leaves out: kernel threads, the OS's placeholder
+    // processes, and anything with no memory, executable or command line at all.
+    // Every user program has resident memory, so a quiet one is never caught;
+    // zombies are kept since they point at a parent that needs attention.
+    pub fn is_kernel(&self) -> bool {
+        self.is_kernel_thread()
+            || KERNEL_PROCESSES.contains(&(self.pid, self.name.as_str()))
+            || (self.mem == 0 && self.exe.is_none() && self.cmd.is_empty() && self.status != "Zombie")
+    }
+
+    // Details the OS wouldn't let us read, typically another user's process without
+    // root: no exe path on Linux, no command line on macOS
+    pub fn is_restricted(&self) -> bool {
+        !self.is_kernel_thread() && (self.exe.is_none() || self.cmd.is_empty())
+    }
+}
+
+// (PID, name) of the idle and kernel pseudo-processes: Windows, macOS, FreeBSD
+const KERNEL_PROCESSES: &[(u32, &str)] = &[(0, "System Idle Process"), (4, "System"), (0, "kernel_task"), (0, "kernel"), (11, "idle")];
+
+// sysinfo only tracks per-process I/O on these platforms; elsewhere the counters are meaningless
+const PROCESS_DISK_IO_SUPPORTED: bool = cfg!(any(target_os = "linux", target_os = "windows", target_os = "macos", target_os = "freebsd"));
+
+// "cpu3" -> 3. Offline cores disappear from the list, so position alone would shift
+// every later core; falls back to positions if the names don't yield unique numbers.
+fn core_ids(cpus: &[sysinfo::Cpu]) -> Vec<usize> {
+    let ids: Vec<usize> = cpus.iter().enumerate().map(|(i, cpu)| {
+        cpu.name().trim_start_matches(|c: char| !c.is_ascii_digit()).parse().unwrap_or(i)
+    }).collect();
+    let unique: HashSet<&usize> = ids.iter().collect();
+    if unique.len() == ids.len() { ids } else { (0..cpus.len()).collect() }
+}
+
+// UID -> account name (SID -> account on Windows), cached so the user database is
+// only consulted for ids we haven't seen. A miss re-reads it once, which picks up
+// accounts created after launch; ids that still don't resolve are cached as numbers.
+struct UserNames {
+    users: Users,
+    names: HashMap<sysinfo::Uid, String>,
+}
+
+impl UserNames {
+    fn new() -> Self {
+        Self { users: Users::new_with_refreshed_list(), names: HashMap::new() }
+    }
+
+    fn name(&mut self, uid: &sysinfo::Uid) -> String {
+        if let Some(name) = self.names.get(uid) {
+            return name.clone();
+        }
+        if self.users.get_user_by_id(uid).is_none() {
+            self.users.refresh();
+        }
+        let name = self.users.get_user_by_id(uid).map(|u| u.name().to_string()).unwrap_or_else(|| uid.to_string());
+        self.names.insert(uid.clone(), name.clone());
+        name
+    }
+}
+
+// (buffers, cached) in bytes from /proc/meminfo
+#[cfg(target_os = "linux")]
+fn mem_cache() -> (u64, u64) {
+    let Ok(meminfo) = std::fs::read_to_string("/proc/meminfo") else { return (0, 0) };
+    let field = |name: &str| -> u64 {
+        meminfo.lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+            .and_then(|rest| rest.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+            .map_or(0, |kb| kb * 1024)
+    };
+    (field("Buffers"), field("Cached") + field("SReclaimable"))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn mem_cache() -> (u64, u64) {
+    (0, 0)
+}
+
+// Cumulative pages swapped (in, out) since boot, from pswpin/pswpout in /proc/vmstat
+#[cfg(target_os = "linux")]
+fn swap_pages() -> Option<(u64, u64)> {
+    let vmstat = std::fs::read_to_string("/proc/vmstat").ok()?;
+    let field = |name: &str| vmstat.lines().find_map(|line| line.strip_prefix(name)?.strip_prefix(' ')?.trim().parse::<u64>().ok());
+    Some((field("pswpin")?, field("pswpout")?))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn swap_pages() -> Option<(u64, u64)> {
+    None
+}
+
+// /proc/stat, read once per refresh for both `kernel_counters` and `cpu_times`
+#[cfg(target_os = "linux")]
+fn proc_stat() -> Option<String> {
+    std::fs::read_to_string("/proc/stat").ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn proc_stat() -> Option<String> {
+    None
+}
+
+// Cumulative (context switches, interrupts) since boot: the ctxt and intr lines of /proc/stat
+fn kernel_counters(stat: &str) -> Option<(u64, u64)> {
+    // "intr" is followed by per-IRQ counts; its first number is the total
+    let field = |name: &str| stat.lines().find_map(|line| line.strip_prefix(name)?.split_whitespace().next()?.parse::<u64>().ok());
+    Some((field("ctxt ")?, field("intr ")?))
+}
+
+// Aggregate CPU jiffies from the "cpu" line of /proc/stat (the sum over every core):
+// user nice system idle iowait irq softirq steal. Guest time is already in user.
+fn cpu_times(stat: &str) -> Option<[u64; 8]> {
+    let mut fields = stat.lines().find_map(|line| line.strip_prefix("cpu "))?.split_whitespace().map(|f| f.parse::<u64>().ok());
+    let mut times = [0; 8];
+    for t in &mut times {
+        *t = fields.next()??;
+    }
+    Some(times)
+}
+
+// Where CPU time went between two `cpu_times` readings
+fn cpu_breakdown(prev: Option<[u64; 8]>, now: Option<[u64; 8]>) -> Option<CpuBreakdown> {
+    let (prev, now) = (prev?, now?);
+    let d: Vec<f32> = now.iter().zip(prev).map(|(n, p)| n.saturating_sub(p) as f32).collect();
+    let total: f32 = d.iter().sum();
+    let pct = |v: f32| v * 100.0 / total;
+    (total > 0.0).then(|| CpuBreakdown { user: pct(d[0] + d[1]), system: pct(d[2] + d[5] + d[6]), iowait: pct(d[4]), steal: pct(d[7]) })
+}
+
+// Per-second rates of a pair of cumulative counters; None until both readings exist
+fn counter_rates(prev: Option<(u64, u64)>, now: Option<(u64, u64)>, secs: f64) -> Option<(f64, f64)> {
+    let ((prev_a, prev_b), (a, b)) = (prev?, now?);
+    (secs > 0.0).then(|| (a.saturating_sub(prev_a) as f64 / secs, b.saturating_sub(prev_b) as f64 / secs))
+}
+
+// Milliseconds each block device has spent doing I/O (io_ticks), keyed by kernel name ("sda", "dm-0")
+#[cfg(target_os = "linux")]
+fn io_ticks() -> HashMap<String, u64> {
+    let Ok(stats) = std::fs::read_to_string("/proc/diskstats") else { return HashMap::new() };
+    stats.lines().filter_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        Some((fields.get(2)?.to_string(), fields.get(12)?.parse().ok()?))
+    }).collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn io_ticks() -> HashMap<String, u64> {
+    HashMap::new()
+}
+
+// Usage of one mounted filesystem. Older recordings stored this as a
+// (name, used, total) array, which still deserializes.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DiskInfo {
+    pub name: String,
+    pub used: u64,
+    pub total: u64,
+    #[serde(default)]
+    pub fs: String,    // "ext4", "btrfs", ...
+    #[serde(default)]
+    pub mount: String, // Mount point
+}
+
+impl DiskInfo {
+    pub fn usage_pct(&self) -> Option<f64> {
+        (self.total > 0).then(|| self.used as f64 / self.total as f64 * 100.0)
+    }
+}
+
+// Throughput of one disk over the last slow interval. Recorded as an array before.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DiskIo {
+    pub name: String,
+    pub read: u64,  // B/s
+    pub write: u64, // B/s
+}
+
+// One sensor reading. Recorded as a (label, celsius) array before.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TempInfo {
+    pub name: String,
+    pub celsius: f32,
+}
+
+// Filesystems --hide-pseudo-fs leaves out: in-memory ones and read-only images
+// (snaps, live media) that always read as full
+const PSEUDO_FILESYSTEMS: &[&str] = &["tmpfs", "devtmpfs", "ramfs", "squashfs", "overlay", "efivarfs"];
+
+fn is_pseudo(disk: &sysinfo::Disk) -> bool {
+    let fs = disk.file_system().to_string_lossy();
+    PSEUDO_FILESYSTEMS.contains(&fs.as_ref()) || disk.name().to_string_lossy().starts_with("/dev/loop")
+}
+
+// "/dev/mapper/root" -> "dm-0": diskstats uses kernel names, so follow symlinks first
+fn kernel_device_name(disk_name: &str) -> Option<String> {
+    let path = std::fs::canonicalize(disk_name).unwrap_or_else(|_| disk_name.into());
+    Some(path.file_name()?.to_string_lossy().to_string())
+}
+
+fn process_info(pid: &sysinfo::Pid, p: &sysinfo::Process, users: &mut UserNames, io_secs: f64) -> ProcessInfo {
+    let disk = p.disk_usage();
+    let (disk_read, disk_write) = if PROCESS_DISK_IO_SUPPORTED && io_secs > 0.0 {
+        ((disk.read_bytes as f64 / io_secs) as u64, (disk.written_bytes as f64 / io_secs) as u64)
+    } else {
+        (0, 0)
+    };
+    ProcessInfo {
+        pid: pid.as_u32(),
+        name: p.name().to_string_lossy().to_string(),
+        cpu: p.cpu_usage(),
+        mem: p.memory(),
+        parent_pid: p.parent().map(|pp| pp.as_u32()),
+        disk_read,
+        disk_write,
+        cmd: p.cmd().iter().map(|a| a.to_string_lossy()).collect::<Vec<_>>().join(" "),
+        exe: p.exe().map(|e| e.display().to_string()),
+        user: p.user_id().map(|uid| users.name(uid)),
+        start_time: p.start_time(),
+        threads: p.tasks().map(|t| t.len()),
+        disk_read_total: disk.total_read_bytes,
+        disk_write_total: disk.total_written_bytes,
+        status: p.status().to_string(),
+        cwd: p.cwd().map(|c| c.display().to_string()),
+    }
+}
+
+// Share of all CPU time, in percent, over the last slow interval. Idle is the rest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CpuBreakdown {
+    pub user: f32,   // Including nice
+    pub system: f32, // Including hard and soft interrupts
+    pub iowait: f32,
+    pub steal: f32,  // Taken by the hypervisor for other guests
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SystemStats {
     pub cpu_usage: Vec<f32>,
+    pub cpu_ids: Vec<usize>, // Stable core number for each `cpu_usage` entry (survives hotplug)
     pub total_cpu_usage: f32,
+    #[serde(default)]
+    pub cpu_breakdown: Option<CpuBreakdown>, // Linux only
     pub ram_used: u64,
     pub ram_total: u64,
+    pub ram_available: u64, // Free plus reclaimable; what new allocations can actually get
+    pub ram_free: u64,
+    pub ram_buffers: u64,   // Linux only (0 elsewhere)
+    pub ram_cached: u64,    // Page cache + reclaimable slab, Linux only
     pub swap_used: u64,
     pub swap_total: u64,
+    #[serde(default)]
+    pub swap_in_rate: f64,  // Pages swapped in per second; 0 where /proc/vmstat isn't available
+    #[serde(default)]
+    pub swap_out_rate: f64, // Pages swapped out per second
+    #[serde(default)]
+    pub ctx_switch_rate: Option<f64>, // Context switches per second; None where unsupported
+    #[serde(default)]
+    pub interrupt_rate: Option<f64>,  // Interrupts per second
     pub rx_bytes: u64,
     pub tx_bytes: u64,
     pub rx_speed: u64,
     pub tx_speed: u64,
-    pub temperatures: Vec<(String, f32)>,
-    pub processes: Vec<ProcessInfo>,
-    pub disks: Vec<(String, u64, u64)>,
+    pub temperatures: Vec<TempInfo>,
+    pub processes: Option<Arc<Vec<ProcessInfo>>>, // Only on slow ticks, when the list is refreshed
+    pub disks: Vec<DiskInfo>,
+    pub disk_io: Vec<DiskIo>,             // Same order as `disks`
+    pub disk_busy: Vec<(String, f32)>,    // (name, % of time with I/O in flight); empty where unsupported
+    #[serde(default)]
+    pub disk_health: Vec<(String, DiskHealth)>, // Disks a temperature sensor could be matched to
+    pub connections: Option<ConnCounts>,  // TCP sockets by state; None where unsupported
+    #[serde(default)]
+    pub sockets: Vec<Connection>,         // Open TCP sockets, only while the UI asked for them
+    #[serde(default)]
+    pub cpu_brand: String,                // Model name of the first CPU; empty if unknown
+    #[serde(default)]
+    pub physical_cores: Option<usize>,
+    #[serde(default)]
+    pub logical_cores: usize,
+    #[serde(default)]
+    pub refresh_ms: f32,                  // Wall time of the last slow refresh (processes, disks, sensors, ...)
+    #[serde(skip, default = "Instant::now")] // Not portable; --replay re-stamps on arrival
     pub timestamp: Instant,
     // NEW FIELDS
     pub uptime: u64,
     pub load_avg: (f64, f64, f64),
+    pub battery: Option<BatteryInfo>, // None on desktops / without the `battery` feature
+}
+
+impl SystemStats {
+    // (in use, reclaimable cache, free) in bytes, summing to ram_total. "In use" is
+    // what can't be reclaimed, so a full page cache doesn't read as full RAM.
+    pub fn ram_breakdown(&self) -> (u64, u64, u64) {
+        let used = if self.ram_available > 0 { self.ram_total.saturating_sub(self.ram_available) } else { self.ram_used };
+        let cache = (self.ram_buffers + self.ram_cached).min(self.ram_total.saturating_sub(used));
+        (used, cache, self.ram_total.saturating_sub(used + cache))
+    }
+
+    // "AMD Ryzen 9 5900X (12C/24T)", or None when the model isn't known
+    pub fn cpu_label(&self) -> Option<String> {
+        if self.cpu_brand.is_empty() {
+            return None;
+        }
+        Some(match self.physical_cores {
+            Some(cores) => format!("{} ({}C/{}T)", self.cpu_brand, cores, self.logical_cores),
+            None => format!("{} ({}T)", self.cpu_brand, self.logical_cores),
+        })
+    }
+}
+
+// CPU and memory are refreshed, and one sample sent, per fast tick. Charts plot a point
+// every 100ms from the samples in between, so a couple per point is plenty.
+const FAST_INTERVAL: Duration = Duration::from_millis(50);
+const SLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+// --adaptive: after ADAPTIVE_STABLE_TICKS quiet slow ticks in a row the slow interval
+// doubles, up to ADAPTIVE_MAX_INTERVAL. "Quiet" means total CPU rose less than
+// ADAPTIVE_CPU_DELTA points, combined network throughput rose no more than 25% (or
+// stayed under ADAPTIVE_NET_FLOOR), and no process started or exited. Only rises count:
+// backing off lowers our own CPU use, which must not read as new activity. CPU is the
+// mean over each slow-tick window; a rise past the threshold mid-window drops straight
+// back to SLOW_INTERVAL.
+const ADAPTIVE_MAX_INTERVAL: Duration = Duration::from_secs(2);
+const ADAPTIVE_STABLE_TICKS: u32 = 4;
+const ADAPTIVE_CPU_DELTA: f32 = 10.0;
+const ADAPTIVE_NET_FLOOR: u64 = 16 * 1024; // B/s treated as idle
+const ADAPTIVE_MIN_SAMPLES: u32 = 10; // CPU readings needed before a snap-back check
+const ADAPTIVE_IDLE_SLEEP: Duration = Duration::from_millis(20); // Loop sleep while backed off
+
+// What the adaptive heuristic compares between slow ticks
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Activity {
+    cpu: f32,
+    net: u64,            // rx + tx B/s
+    procs: (usize, u64), // Process count and PID sum: changes whenever the set does
+}
+
+impl Activity {
+    fn is_quiet_since(&self, prev: &Activity) -> bool {
+        let net_quiet = self.net < ADAPTIVE_NET_FLOOR || self.net <= prev.net + prev.net / 4;
+        self.cpu - prev.cpu < ADAPTIVE_CPU_DELTA && net_quiet && self.procs == prev.procs
+    }
 }
 
 pub enum MonitorEvent {
-    Stats(SystemStats),
+    Stats(Box<SystemStats>),
+    LogError(String),      // CSV logging failed and has been switched off
+    RecordError(String),   // --record failed and has been switched off
+    CommandResult(String), // Outcome of a MonitorCommand, for the footer
+    ReplayEnded(String),   // --replay reached the end of the file (or a bad line)
+}
+
+// Requests from the UI, executed on the monitor thread (which owns the process table)
+#[derive(Debug, Clone)]
+pub enum MonitorCommand {
+    Kill { pid: u32, signal: KillSignal },
+    WatchSockets(bool), // Collect the per-socket list (costly: walks every fd table)
+    Shutdown,           // Stop the sampling thread
+}
+
+// Signals offered by the kill menu (Stop/Cont also bound to 'z'/'Z'). Windows has
+// no signals; the terminating choices kill the process there and the rest are refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillSignal {
+    Term,
+    Kill,
+    Hup,
+    Stop,
+    Cont,
+}
+
+impl KillSignal {
+    pub const ALL: [KillSignal; 5] = [KillSignal::Term, KillSignal::Kill, KillSignal::Hup, KillSignal::Stop, KillSignal::Cont];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            KillSignal::Term => "SIGTERM",
+            KillSignal::Kill => "SIGKILL",
+            KillSignal::Hup => "SIGHUP",
+            KillSignal::Stop => "SIGSTOP",
+            KillSignal::Cont => "SIGCONT",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            KillSignal::Term => "ask to exit",
+            KillSignal::Kill => "force kill",
+            KillSignal::Hup => "hang up / reload",
+            KillSignal::Stop => "suspend",
+            KillSignal::Cont => "resume",
+        }
+    }
+}
+
+// None when the platform can't deliver the signal
+#[cfg(unix)]
+fn send_signal(process: &sysinfo::Process, signal: KillSignal) -> Option<bool> {
+    process.kill_with(match signal {
+        KillSignal::Term => sysinfo::Signal::Term,
+        KillSignal::Kill => sysinfo::Signal::Kill,
+        KillSignal::Hup => sysinfo::Signal::Hangup,
+        KillSignal::Stop => sysinfo::Signal::Stop,
+        KillSignal::Cont => sysinfo::Signal::Continue,
+    })
+}
+
+#[cfg(not(unix))]
+fn send_signal(process: &sysinfo::Process, signal: KillSignal) -> Option<bool> {
+    match signal {
+        KillSignal::Stop | KillSignal::Cont => None,
+        _ => Some(process.kill()),
+    }
 }
 
 pub struct Monitor {
     tx: Sender<MonitorEvent>,
+    commands: Option<Receiver<MonitorCommand>>,
     sys: System,
     networks: Networks,
     disks: Disks,
     components: Components,
+    users: UserNames,
     target_interval: Duration,
+    logger: Option<CsvLogger>,
+    recorder: Option<Recorder>,  // --record
+    shared: Option<SharedStats>, // Latest sample for the metrics endpoint
+    adaptive: bool,
+    list_sockets: bool, // MonitorCommand::WatchSockets
+    hide_pseudo_fs: bool,
+    state: SampleState,
 }
 
 impl Monitor {
@@ -51,115 +483,564 @@ impl Monitor {
         let networks = Networks::new_with_refreshed_list();
         let disks = Disks::new_with_refreshed_list();
         let components = Components::new_with_refreshed_list();
+        let users = UserNames::new();
         sys.refresh_all();
-        
+        let state = SampleState::new(&sys, &networks, &disks);
+
         Self {
             tx,
+            commands: None,
             sys,
             networks,
             disks,
             components,
-            target_interval: Duration::from_micros(1000), // 1ms
+            users,
+            target_interval: FAST_INTERVAL,
+            logger: None,
+            recorder: None,
+            shared: None,
+            adaptive: false,
+            list_sockets: false,
+            hide_pseudo_fs: false,
+            state,
+        }
+    }
+
+    pub fn with_logger(mut self, logger: CsvLogger) -> Self {
+        self.logger = Some(logger);
+        self
+    }
+
+    pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    pub fn with_shared_stats(mut self, shared: SharedStats) -> Self {
+        self.shared = Some(shared);
+        self
+    }
+
+    pub fn with_commands(mut self, commands: Receiver<MonitorCommand>) -> Self {
+        self.commands = Some(commands);
+        self
+    }
+
+    // Some(message) for the footer
+    fn execute(&mut self, command: MonitorCommand) -> Option<String> {
+        match command {
+            MonitorCommand::Kill { pid, signal } => {
+                let Some(process) = self.sys.process(sysinfo::Pid::from_u32(pid)) else {
+                    return Some(format!("PROCESS {} IS GONE", pid));
+                };
+                let name = process.name().to_string_lossy().to_string();
+                Some(match send_signal(process, signal) {
+                    Some(true) => format!("SENT {} TO {} ({})", signal.name(), pid, name),
+                    Some(false) => format!("FAILED TO SEND {} TO {} ({}): permission denied?", signal.name(), pid, name),
+                    None => format!("{} IS NOT SUPPORTED ON THIS PLATFORM", signal.name()),
+                })
+            }
+            MonitorCommand::WatchSockets(on) => {
+                self.list_sockets = on;
+                None
+            }
+            MonitorCommand::Shutdown => None, // The loop stops before executing it
+        }
+    }
+
+    pub fn with_adaptive(mut self, adaptive: bool) -> Self {
+        self.adaptive = adaptive;
+        self
+    }
+
+    pub fn with_hide_pseudo_fs(mut self, hide: bool) -> Self {
+        self.hide_pseudo_fs = hide;
+        self
+    }
+
+    // One sample outside the thread loop (--snapshot). CPU usage is measured between
+    // two refreshes, so this blocks for sysinfo's minimum update interval first.
+    pub fn snapshot(mut self) -> SystemStats {
+        thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        self.state.slow_interval = Duration::ZERO; // Everything is due
+        self.sample()
+    }
+
+    // Refreshes whatever is due (CPU and RAM every target interval, the rest every
+    // slow interval) and builds a sample from it. The thread loop calls this on its
+    // own schedule; `state.slow_tick` tells it whether the slow readings are new.
+    pub fn sample(&mut self) -> SystemStats {
+        let now = Instant::now();
+        if now.duration_since(self.state.last_fast_tick) >= self.target_interval {
+            self.sys.refresh_cpu_all();
+            self.sys.refresh_memory();
+            self.state.last_fast_tick = now;
+        }
+        self.state.slow_tick = now.duration_since(self.state.last_slow_tick) >= self.state.slow_interval;
+        if self.state.slow_tick {
+            self.refresh_slow(now);
+        }
+        self.collect(now)
+    }
+
+    // Processes, disks, network, sensors: the expensive part, done once per slow tick.
+    // Rates are deltas against the counters `state` kept from the previous refresh.
+    fn refresh_slow(&mut self, now: Instant) {
+        let state = &mut self.state;
+        let refresh_start = Instant::now();
+        self.sys.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_memory()
+                .with_cpu()
+                .with_disk_usage()
+                .with_exe(UpdateKind::OnlyIfNotSet)
+                .with_cmd(UpdateKind::OnlyIfNotSet)
+                .with_user(UpdateKind::OnlyIfNotSet)
+                .with_cwd(UpdateKind::OnlyIfNotSet)
+                .with_tasks(),
+        );
+        self.networks.refresh(true);
+        self.disks.refresh(true);
+        self.components.refresh(true);
+        (state.ram_buffers, state.ram_cached) = mem_cache();
+        state.proc_refresh_secs = now.duration_since(state.last_slow_tick).as_secs_f64();
+        let pages = swap_pages();
+        (state.swap_in_rate, state.swap_out_rate) = counter_rates(state.swap_pages, pages, state.proc_refresh_secs).unwrap_or((0.0, 0.0));
+        state.swap_pages = pages;
+        let stat = proc_stat();
+        let times = stat.as_deref().and_then(cpu_times);
+        state.cpu_breakdown = cpu_breakdown(state.cpu_times, times);
+        state.cpu_times = times;
+        let counters = stat.as_deref().and_then(kernel_counters);
+        state.kernel_rates = counter_rates(state.kernel_counters, counters, state.proc_refresh_secs);
+        state.kernel_counters = counters;
+        state.last_slow_tick = now;
+
+        // Disk throughput: delta of cumulative counters over the slow interval.
+        // A disk mounted since the last refresh has no previous value, so it reports 0.
+        let disk_delta = now.duration_since(state.last_disk_check).as_secs_f64();
+        let prev_disk_io = &mut state.prev_disk_io;
+        state.disk_io = listed_disks(&self.disks, self.hide_pseudo_fs).map(|d| {
+            let usage = d.usage();
+            let key = d.mount_point().to_string_lossy().to_string();
+            let (read, write) = match prev_disk_io.get(&key) {
+                Some(&(prev_r, prev_w)) if disk_delta > 0.0 => (
+                    (usage.total_read_bytes.saturating_sub(prev_r) as f64 / disk_delta) as u64,
+                    (usage.total_written_bytes.saturating_sub(prev_w) as f64 / disk_delta) as u64,
+                ),
+                _ => (0, 0),
+            };
+            prev_disk_io.insert(key, (usage.total_read_bytes, usage.total_written_bytes));
+            DiskIo { name: d.name().to_string_lossy().to_string(), read, write }
+        }).collect();
+        // Busy %: share of the interval the device had I/O in flight
+        let ticks = io_ticks();
+        let elapsed_ms = disk_delta * 1000.0;
+        let prev_io_ticks = &state.prev_io_ticks;
+        state.disk_busy = listed_disks(&self.disks, self.hide_pseudo_fs).filter_map(|d| {
+            let name = d.name().to_string_lossy().to_string();
+            let device = kernel_device_name(&name)?;
+            let (now_ticks, prev) = (*ticks.get(&device)?, *prev_io_ticks.get(&device)?);
+            let busy = if elapsed_ms > 0.0 { now_ticks.saturating_sub(prev) as f64 / elapsed_ms * 100.0 } else { 0.0 };
+            Some((name, busy.min(100.0) as f32))
+        }).collect();
+        state.prev_io_ticks = ticks;
+        state.last_disk_check = now;
+        let sensors: Vec<disk_health::Sensor> = self.components.iter()
+            .filter_map(|c| Some((c.label().to_string(), c.temperature()?, c.critical())))
+            .collect();
+        state.disk_health = listed_disks(&self.disks, self.hide_pseudo_fs).filter_map(|d| {
+            let name = d.name().to_string_lossy().to_string();
+            let health = disk_health::sensor_for(&kernel_device_name(&name)?, &sensors)?;
+            Some((name, health))
+        }).collect();
+
+        state.battery = read_battery();
+        state.connections = sockets::conn_counts();
+        state.socket_list = if self.list_sockets { sockets::connections() } else { Vec::new() };
+
+        let (prev_rx, prev_tx) = state.net_totals;
+        state.net_totals = net_totals(&self.networks);
+        let net_delta = now.duration_since(state.last_net_check).as_secs_f64();
+        if net_delta > 0.0 {
+            state.rx_speed = (state.net_totals.0.saturating_sub(prev_rx) as f64 / net_delta) as u64;
+            state.tx_speed = (state.net_totals.1.saturating_sub(prev_tx) as f64 / net_delta) as u64;
+        }
+        state.last_net_check = now;
+        state.refresh_ms = refresh_start.elapsed().as_secs_f32() * 1000.0;
+        state.warmed_up = true;
+    }
+
+    // Builds a sample from what sysinfo and `state` currently hold; refreshes nothing
+    // but the per-process user names
+    fn collect(&mut self, now: Instant) -> SystemStats {
+        let state = &self.state;
+        let cpus = self.sys.cpus();
+        let cpu_usage: Vec<f32> = cpus.iter().map(|cpu| cpu.cpu_usage()).collect();
+        let cpu_ids = core_ids(cpus);
+        let total_cpu_usage = if !cpu_usage.is_empty() {
+            cpu_usage.iter().sum::<f32>() / cpu_usage.len() as f32
+        } else { 0.0 };
+
+        // Every process: the UI filters, sorts and scrolls the full set (and --top caps what it lists).
+        // Between slow ticks the list hasn't changed, so fast samples leave it out.
+        let procs = state.slow_tick.then(|| Arc::new(self.sys.processes().iter()
+            .map(|(pid, p)| process_info(pid, p, &mut self.users, state.proc_refresh_secs))
+            .collect::<Vec<_>>()));
+
+        let disks_info = listed_disks(&self.disks, self.hide_pseudo_fs).map(|d| DiskInfo {
+            name: d.name().to_string_lossy().to_string(),
+            used: d.total_space() - d.available_space(),
+            total: d.total_space(),
+            fs: d.file_system().to_string_lossy().to_string(),
+            mount: d.mount_point().to_string_lossy().to_string(),
+        }).collect();
+
+        // Sensors without a reading are left out rather than reported as 0°C
+        let temps = self.components.iter().filter_map(|c| {
+            c.temperature().map(|celsius| TempInfo { name: c.label().to_string(), celsius })
+        }).collect();
+        
+        // Load Average
+        let load = System::load_average();
+
+        SystemStats {
+            cpu_usage,
+            cpu_ids,
+            total_cpu_usage,
+            cpu_breakdown: state.cpu_breakdown,
+            ram_used: self.sys.used_memory(),
+            ram_total: self.sys.total_memory(),
+            ram_available: self.sys.available_memory(),
+            ram_free: self.sys.free_memory(),
+            ram_buffers: state.ram_buffers,
+            ram_cached: state.ram_cached,
+            swap_used: self.sys.used_swap(),
+            swap_total: self.sys.total_swap(),
+            swap_in_rate: state.swap_in_rate,
+            swap_out_rate: state.swap_out_rate,
+            ctx_switch_rate: state.kernel_rates.map(|r| r.0),
+            interrupt_rate: state.kernel_rates.map(|r| r.1),
+            rx_bytes: state.net_totals.0,
+            tx_bytes: state.net_totals.1,
+            rx_speed: state.rx_speed,
+            tx_speed: state.tx_speed,
+            temperatures: temps,
+            processes: procs,
+            disks: disks_info,
+            disk_io: state.disk_io.clone(),
+            disk_busy: state.disk_busy.clone(),
+            disk_health: state.disk_health.clone(),
+            connections: state.connections,
+            sockets: state.socket_list.clone(),
+            cpu_brand: state.cpu_brand.clone(),
+            physical_cores: state.physical_cores,
+            logical_cores: state.logical_cores,
+            refresh_ms: state.refresh_ms,
+            timestamp: now,
+            uptime: System::uptime(),
+            load_avg: (load.one, load.five, load.fifteen),
+            battery: state.battery.clone(),
         }
     }
 
-    pub fn run(mut self) {
+    // Samples until a `MonitorCommand::Shutdown` arrives or the receiving end is dropped
+    pub fn run(mut self) -> JoinHandle<()> {
         thread::spawn(move || {
-            let mut last_fast_tick = Instant::now();
-            let mut last_slow_tick = Instant::now();
-            
-            let mut prev_rx = 0;
-            let mut prev_tx = 0;
-            let mut last_net_check = Instant::now();
+            let mut quiet_ticks = 0;
+            let mut last_activity: Option<Activity> = None;
+            let mut cpu_window = (0.0_f32, 0u32); // Sum and count of CPU readings since the last slow tick
 
             loop {
+                let pending: Vec<MonitorCommand> = self.commands.as_ref().map(|c| c.try_iter().collect()).unwrap_or_default();
+                for command in pending {
+                    if matches!(command, MonitorCommand::Shutdown) {
+                        return;
+                    }
+                    if let Some(msg) = self.execute(command) {
+                        let _ = self.tx.send(MonitorEvent::CommandResult(msg));
+                    }
+                }
+
+                // Only a fresh fast refresh makes a new sample; until then there's nothing to send
+                let next_tick = self.state.last_fast_tick + self.target_interval;
                 let now = Instant::now();
-                
-                // 1. FAST LOOP (CPU, RAM)
-                if now.duration_since(last_fast_tick) >= self.target_interval {
-                    self.sys.refresh_cpu_all();
-                    self.sys.refresh_memory();
-                    last_fast_tick = now;
+                if now < next_tick {
+                    thread::sleep(next_tick - now);
+                    continue;
                 }
 
-                // 2. SLOW LOOP (Processes, Disk, Net, Temp)
-                let slow_interval = Duration::from_millis(500);
-                if now.duration_since(last_slow_tick) >= slow_interval {
-                    self.sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
-                    self.networks.refresh(true);
-                    self.disks.refresh(true);
-                    self.components.refresh(true);
-                    last_slow_tick = now;
+                let stats = self.sample();
+                // Until the first slow refresh the rates have nothing to be a delta against
+                // and CPU usage covers too short a span; charts would open on a fake dip
+                if !self.state.warmed_up {
+                    continue;
                 }
+                let slow_tick = self.state.slow_tick;
+                let slow_interval = &mut self.state.slow_interval;
 
-                // --- DATA AGGREGATION ---
-                
-                let cpus = self.sys.cpus();
-                let cpu_usage: Vec<f32> = cpus.iter().map(|cpu| cpu.cpu_usage()).collect();
-                let total_cpu_usage = if !cpu_usage.is_empty() {
-                    cpu_usage.iter().sum::<f32>() / cpu_usage.len() as f32
-                } else { 0.0 };
-
-                let time_delta = now.duration_since(last_net_check).as_secs_f64();
-                let (mut curr_rx, mut curr_tx) = (0, 0);
-                for (_, data) in &self.networks {
-                    curr_rx += data.total_received();
-                    curr_tx += data.total_transmitted();
+                if self.adaptive {
+                    // Per-loop CPU readings are far too noisy at this rate; compare window means
+                    cpu_window.0 += stats.total_cpu_usage;
+                    cpu_window.1 += 1;
+                    let activity = Activity {
+                        cpu: cpu_window.0 / cpu_window.1 as f32,
+                        net: stats.rx_speed + stats.tx_speed,
+                        procs: (self.sys.processes().len(), self.sys.processes().keys().map(|p| p.as_u32() as u64).sum()),
+                    };
+                    match last_activity {
+                        Some(prev) if slow_tick => {
+                            if activity.is_quiet_since(&prev) {
+                                quiet_ticks += 1;
+                                if quiet_ticks >= ADAPTIVE_STABLE_TICKS {
+                                    *slow_interval = (*slow_interval * 2).min(ADAPTIVE_MAX_INTERVAL);
+                                    quiet_ticks = 0;
+                                }
+                            } else {
+                                *slow_interval = SLOW_INTERVAL;
+                                quiet_ticks = 0;
+                            }
+                        }
+                        // Snap back as soon as CPU climbs, without waiting out a long slow interval
+                        Some(prev) if *slow_interval > SLOW_INTERVAL
+                            && cpu_window.1 >= ADAPTIVE_MIN_SAMPLES
+                            && activity.cpu - prev.cpu >= ADAPTIVE_CPU_DELTA =>
+                        {
+                            *slow_interval = SLOW_INTERVAL;
+                            quiet_ticks = 0;
+                        }
+                        _ => {}
+                    }
+                    if slow_tick || last_activity.is_none() {
+                        last_activity = Some(activity);
+                        cpu_window = (0.0, 0);
+                    }
                 }
-                
-                let rx_speed = if time_delta > 0.0 { ((curr_rx - prev_rx) as f64 / time_delta) as u64 } else { 0 };
-                let tx_speed = if time_delta > 0.0 { ((curr_tx - prev_tx) as f64 / time_delta) as u64 } else { 0 };
-                
-                if time_delta >= 0.5 {
-                    prev_rx = curr_rx;
-                    prev_tx = curr_tx;
-                    last_net_check = now;
+
+                // CSV Logging (once per slow tick)
+                if slow_tick
+                    && let Some(logger) = &mut self.logger
+                    && let Err(e) = logger.write(&stats)
+                {
+                    self.logger = None;
+                    let _ = self.tx.send(MonitorEvent::LogError(e.to_string()));
                 }
 
-                let mut procs: Vec<ProcessInfo> = self.sys.processes().iter()
-                    .map(|(pid, p)| ProcessInfo {
-                        pid: pid.as_u32(),
-                        name: p.name().to_string_lossy().to_string(),
-                        cpu: p.cpu_usage(),
-                        mem: p.memory(),
-                    })
-                    .collect();
-                procs.sort_by(|a, b| b.cpu.partial_cmp(&a.cpu).unwrap_or(std::cmp::Ordering::Equal));
-                procs.truncate(50); // Keep more for scrolling
-
-                let disks_info = self.disks.iter().map(|d| {
-                    (d.name().to_string_lossy().to_string(), d.total_space() - d.available_space(), d.total_space())
-                }).collect();
-
-                let temps = self.components.iter().map(|c| {
-                    (c.label().to_string(), c.temperature().unwrap_or(0.0))
-                }).collect();
-                
-                // Load Average
-                let load = System::load_average();
-
-                let stats = SystemStats {
-                    cpu_usage,
-                    total_cpu_usage,
-                    ram_used: self.sys.used_memory(),
-                    ram_total: self.sys.total_memory(),
-                    swap_used: self.sys.used_swap(),
-                    swap_total: self.sys.total_swap(),
-                    rx_bytes: curr_rx,
-                    tx_bytes: curr_tx,
-                    rx_speed,
-                    tx_speed,
-                    temperatures: temps,
-                    processes: procs,
-                    disks: disks_info,
-                    timestamp: now,
-                    uptime: System::uptime(),
-                    load_avg: (load.one, load.five, load.fifteen),
-                };
+                if let Some(recorder) = &mut self.recorder
+                    && let Err(e) = recorder.write(&stats)
+                {
+                    self.recorder = None;
+                    let _ = self.tx.send(MonitorEvent::RecordError(e.to_string()));
+                }
+
+                if slow_tick && let Some(shared) = &self.shared {
+                    *shared.lock() = Some(stats.clone());
+                }
 
-                let _ = self.tx.send(MonitorEvent::Stats(stats));
-                thread::sleep(Duration::from_micros(500)); 
+                if self.tx.send(MonitorEvent::Stats(Box::new(stats))).is_err() {
+                    return;
+                }
+                if self.state.slow_interval > SLOW_INTERVAL {
+                    thread::sleep(ADAPTIVE_IDLE_SLEEP);
+                }
             }
-        });
+        })
+    }
+}
+
+// Everything a sample needs besides the sysinfo handles: when each refresh last ran,
+// counters from the previous slow refresh (for rates) and the slow readings held
+// until the next one.
+struct SampleState {
+    last_fast_tick: Instant,
+    last_slow_tick: Instant,
+    slow_interval: Duration, // SLOW_INTERVAL, or longer while --adaptive finds things quiet
+    slow_tick: bool,         // Whether the last sample refreshed the slow readings
+    warmed_up: bool,         // A slow refresh has run, so every rate is a real delta
+    proc_refresh_secs: f64, // Span covered by the per-process I/O counters
+
+    // Network rates are only recomputed when the counters are refreshed (slow tick)
+    // and held in between; dividing by a growing delta on every loop made them sawtooth
+    net_totals: (u64, u64), // Cumulative rx, tx bytes at the last refresh
+    rx_speed: u64,
+    tx_speed: u64,
+    last_net_check: Instant,
+
+    // Disk I/O counters keyed by mount point (names aren't unique)
+    prev_disk_io: HashMap<String, (u64, u64)>,
+    disk_io: Vec<DiskIo>,
+    last_disk_check: Instant,
+    prev_io_ticks: HashMap<String, u64>,
+    disk_busy: Vec<(String, f32)>,
+    disk_health: Vec<(String, DiskHealth)>,
+
+    connections: Option<ConnCounts>,
+    socket_list: Vec<Connection>,
+    ram_buffers: u64,
+    ram_cached: u64,
+    swap_pages: Option<(u64, u64)>, // pswpin, pswpout at the last slow tick
+    swap_in_rate: f64,
+    swap_out_rate: f64,
+    kernel_counters: Option<(u64, u64)>, // ctxt, intr at the last slow tick
+    cpu_times: Option<[u64; 8]>,
+    cpu_breakdown: Option<CpuBreakdown>,
+    kernel_rates: Option<(f64, f64)>,
+    battery: Option<BatteryInfo>,
+    refresh_ms: f32,
+
+    // Fixed for the life of the process
+    cpu_brand: String,
+    physical_cores: Option<usize>,
+    logical_cores: usize,
+}
+
+impl SampleState {
+    fn new(sys: &System, networks: &Networks, disks: &Disks) -> Self {
+        let now = Instant::now();
+        let (ram_buffers, ram_cached) = mem_cache();
+        let stat = proc_stat();
+        Self {
+            last_fast_tick: now,
+            last_slow_tick: now,
+            slow_interval: SLOW_INTERVAL,
+            slow_tick: false,
+            warmed_up: false,
+            proc_refresh_secs: 0.0,
+            net_totals: net_totals(networks), // So the first rate isn't everything since boot
+            rx_speed: 0,
+            tx_speed: 0,
+            last_net_check: now,
+            prev_disk_io: disks.iter() // Seeded like `net_totals`, so the first disk rates are real
+                .map(|d| (d.mount_point().to_string_lossy().to_string(), (d.usage().total_read_bytes, d.usage().total_written_bytes)))
+                .collect(),
+            disk_io: Vec::new(),
+            last_disk_check: now,
+            prev_io_ticks: io_ticks(),
+            disk_busy: Vec::new(),
+            disk_health: Vec::new(),
+            connections: sockets::conn_counts(),
+            socket_list: Vec::new(),
+            ram_buffers,
+            ram_cached,
+            swap_pages: swap_pages(),
+            swap_in_rate: 0.0,
+            swap_out_rate: 0.0,
+            kernel_counters: stat.as_deref().and_then(kernel_counters),
+            cpu_times: stat.as_deref().and_then(cpu_times),
+            cpu_breakdown: None,
+            kernel_rates: None,
+            battery: read_battery(),
+            refresh_ms: 0.0,
+            cpu_brand: sys.cpus().first().map(|c| c.brand().trim().to_string()).unwrap_or_default(),
+            physical_cores: System::physical_core_count(),
+            logical_cores: sys.cpus().len(),
+        }
     }
-}
\ No newline at end of file
+}
+
+// Disks as reported to the UI, loggers and alerts
+fn listed_disks(disks: &Disks, hide_pseudo_fs: bool) -> impl Iterator<Item = &sysinfo::Disk> {
+    disks.iter().filter(move |d| !(hide_pseudo_fs && is_pseudo(d)))
+}
+
+// The platform battery handle isn't `Send`, so it can't travel with the `Monitor` into
+// its thread; each thread that samples opens its own on first use instead
+thread_local! {
+    static BATTERY: RefCell<BatteryReader> = RefCell::new(BatteryReader::new());
+}
+
+fn read_battery() -> Option<BatteryInfo> {
+    BATTERY.with_borrow_mut(|reader| reader.read())
+}
+
+// Bytes received and sent over all interfaces since boot
+fn net_totals(networks: &Networks) -> (u64, u64) {
+    networks.iter().fold((0, 0), |(rx, tx), (_, data)| (rx + data.total_received(), tx + data.total_transmitted()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shutdown_stops_the_sampling_thread() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let (command_tx, command_rx) = crossbeam_channel::unbounded();
+        let handle = Monitor::new(tx).with_commands(command_rx).run();
+        assert!(matches!(rx.recv_timeout(Duration::from_secs(5)), Ok(MonitorEvent::Stats(_))));
+
+        command_tx.send(MonitorCommand::Shutdown).unwrap();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn samples_arrive_at_most_once_per_fast_tick() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let (command_tx, command_rx) = crossbeam_channel::unbounded();
+        let handle = Monitor::new(tx).with_commands(command_rx).run();
+        assert!(matches!(rx.recv_timeout(Duration::from_secs(5)), Ok(MonitorEvent::Stats(_))));
+        let started = Instant::now();
+        thread::sleep(FAST_INTERVAL * 10);
+        command_tx.send(MonitorCommand::Shutdown).unwrap();
+        handle.join().unwrap();
+
+        // A busy machine can only mean fewer samples, so only the upper bound is checked
+        let ticks = started.elapsed().as_millis() / FAST_INTERVAL.as_millis();
+        let samples = rx.try_iter().filter(|e| matches!(e, MonitorEvent::Stats(_))).count();
+        assert!(samples as u128 <= ticks + 1, "{} samples in {} fast ticks", samples, ticks);
+    }
+
+    #[test]
+    fn first_sample_waits_for_a_real_network_delta() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let (command_tx, command_rx) = crossbeam_channel::unbounded();
+        let started = Instant::now();
+        let monitor = Monitor::new(tx).with_commands(command_rx);
+        let (start_rx, start_tx) = monitor.state.net_totals;
+        let handle = monitor.run();
+        let Ok(MonitorEvent::Stats(first)) = rx.recv_timeout(Duration::from_secs(5)) else { panic!("no sample") };
+        command_tx.send(MonitorCommand::Shutdown).unwrap();
+        handle.join().unwrap();
+
+        // Nothing is sent before the first slow refresh, and that refresh measures against
+        // the counters seen at startup: at most what arrived since, never the total since boot
+        assert!(started.elapsed() >= SLOW_INTERVAL);
+        let secs = SLOW_INTERVAL.as_secs_f64();
+        assert!(first.rx_speed as f64 * secs <= first.rx_bytes.saturating_sub(start_rx) as f64);
+        assert!(first.tx_speed as f64 * secs <= first.tx_bytes.saturating_sub(start_tx) as f64);
+    }
+
+    #[test]
+    fn proc_stat_fields_parse_from_one_read() {
+        let stat = "cpu  100 5 50 1000 10 1 2 3 0 0\ncpu0 100 5 50 1000 10 1 2 3 0 0\nintr 4200 10 20\nctxt 9000\n";
+        assert_eq!(cpu_times(stat), Some([100, 5, 50, 1000, 10, 1, 2, 3]));
+        assert_eq!(kernel_counters(stat), Some((9000, 4200)));
+        assert_eq!(cpu_times("cpu  1 2 3\n"), None);
+    }
+
+    #[test]
+    fn cpu_breakdown_folds_the_jiffy_fields() {
+        let prev = [100, 0, 50, 1000, 10, 0, 0, 0];
+        let now = [160, 20, 70, 1250, 30, 5, 5, 20]; // 400 jiffies in all
+        let b = cpu_breakdown(Some(prev), Some(now)).unwrap();
+        assert_eq!(b, CpuBreakdown { user: 20.0, system: 7.5, iowait: 5.0, steal: 5.0 });
+        assert_eq!(cpu_breakdown(Some(now), Some(now)), None);
+    }
+
+    #[test]
+    fn tuple_form_recordings_still_load() {
+        let temp: TempInfo = serde_json::from_str(r#"["cpu", 41.5]"#).unwrap();
+        assert_eq!((temp.name.as_str(), temp.celsius), ("cpu", 41.5));
+        let io: DiskIo = serde_json::from_str(r#"["/dev/sda1", 100, 200]"#).unwrap();
+        assert_eq!((io.name.as_str(), io.read, io.write), ("/dev/sda1", 100, 200));
+    }
+
+    #[test]
+    fn counter_rates_need_two_readings() {
+        assert_eq!(counter_rates(Some((100, 10)), Some((300, 40)), 2.0), Some((100.0, 15.0)));
+        assert_eq!(counter_rates(None, Some((300, 40)), 2.0), None);
+        assert_eq!(counter_rates(Some((500, 10)), Some((300, 40)), 1.0), Some((0.0, 30.0))); // Counter reset
+    }
+}
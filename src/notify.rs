@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::alerts::{ActiveAlert, AlertMetric};
+use crate::units::TempUnit;
+
+const NOTIFY_COOLDOWN: Duration = Duration::from_secs(300);
+
+// Sends a desktop notification when an alert starts firing. The alert engine
+// already only reports a rule once per crossing; the cooldown additionally
+// stops a metric flapping around its threshold from spamming the desktop.
+pub struct Notifier {
+    last_sent: HashMap<AlertMetric, Instant>,
+    unavailable: Arc<AtomicBool>, // Set once the notification service fails (headless box)
+}
+
+impl Notifier {
+    pub fn new() -> Self {
+        Self { last_sent: HashMap::new(), unavailable: Arc::new(AtomicBool::new(false)) }
+    }
+
+    pub fn notify(&mut self, alert: &ActiveAlert, temp_unit: TempUnit, now: Instant) {
+        if self.unavailable.load(Ordering::Relaxed) {
+            return;
+        }
+        let metric = alert.rule.metric;
+        if self.last_sent.get(&metric).is_some_and(|t| now.duration_since(*t) < NOTIFY_COOLDOWN) {
+            return;
+        }
+        self.last_sent.insert(metric, now);
+
+        let summary = format!("{} alert", metric.label());
+        let body = format!(
+            "{} is at {} (threshold {})",
+            metric.label(), metric.format(alert.value, temp_unit), metric.format(alert.rule.threshold, temp_unit)
+        );
+        send(summary, body, self.unavailable.clone());
+    }
+}
+
+// Delivery can block on the session bus, so it happens off the UI thread
+#[cfg(feature = "notify")]
+fn send(summary: String, body: String, unavailable: Arc<AtomicBool>) {
+    std::thread::spawn(move || {
+        let result = notify_rust::Notification::new()
+            .appname("OMNI-MONITOR")
+            .summary(&summary)
+            .body(&body)
+            .show();
+        if result.is_err() {
+            unavailable.store(true, Ordering::Relaxed);
+        }
+    });
+}
+
+#[cfg(not(feature = "notify"))]
+fn send(_summary: String, _body: String, unavailable: Arc<AtomicBool>) {
+    unavailable.store(true, Ordering::Relaxed);
+}
@@ -0,0 +1,154 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+use anyhow::{bail, Context, Result};
+use crossbeam_channel::Sender;
+use crate::monitor::{MonitorEvent, SystemStats};
+
+// The monitor sends far more often than anything visible changes; one sample per
+// chart interval is enough to reproduce every frame of the charts.
+const RECORD_INTERVAL: Duration = Duration::from_millis(100);
+
+// One line of a recording: the sample and when it arrived, relative to the first one.
+// `SystemStats::timestamp` is an `Instant` and can't be stored, so replay re-stamps it.
+#[derive(serde::Serialize)]
+struct FrameOut<'a> {
+    at_ms: u64,
+    stats: &'a SystemStats,
+}
+
+#[derive(serde::Deserialize)]
+struct FrameIn {
+    at_ms: u64,
+    stats: SystemStats,
+}
+
+// --record: appends JSON lines on the monitor thread. Flushed per frame so a
+// recording survives the crash it was meant to capture.
+pub struct Recorder {
+    writer: BufWriter<File>,
+    start: Option<Instant>,
+    last: Option<Instant>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Ok(Self { writer: BufWriter::new(File::create(path)?), start: None, last: None })
+    }
+
+    pub fn write(&mut self, stats: &SystemStats) -> io::Result<()> {
+        if self.last.is_some_and(|t| stats.timestamp.saturating_duration_since(t) < RECORD_INTERVAL) {
+            return Ok(());
+        }
+        let start = *self.start.get_or_insert(stats.timestamp);
+        let at_ms = stats.timestamp.saturating_duration_since(start).as_millis() as u64;
+        serde_json::to_writer(&mut self.writer, &FrameOut { at_ms, stats })?;
+        writeln!(self.writer)?;
+        self.writer.flush()?;
+        self.last = Some(stats.timestamp);
+        Ok(())
+    }
+}
+
+// --replay: feeds a recording into the UI channel at its original cadence (scaled by
+// --replay-speed), in place of the monitor thread. The file is opened here so a bad path fails before the
+// terminal is taken over; a bad line ends the replay with a footer message.
+pub fn replay(path: &Path, tx: Sender<MonitorEvent>, looping: bool, speed: f64) -> Result<()> {
+    File::open(path).with_context(|| format!("failed to open replay file {}", path.display()))?;
+    let path: PathBuf = path.to_path_buf();
+    thread::spawn(move || loop {
+        let Ok(file) = File::open(&path) else {
+            let _ = tx.send(MonitorEvent::ReplayEnded(format!("REPLAY STOPPED: can't reopen {}", path.display())));
+            return;
+        };
+        match play(BufReader::new(file), &tx, speed) {
+            Ok(0) => {
+                let _ = tx.send(MonitorEvent::ReplayEnded("REPLAY EMPTY".to_string()));
+                return;
+            }
+            Ok(_) if looping => continue,
+            Ok(frames) => {
+                let _ = tx.send(MonitorEvent::ReplayEnded(format!("REPLAY FINISHED ({} samples)", frames)));
+                return;
+            }
+            Err(err) => {
+                let _ = tx.send(MonitorEvent::ReplayEnded(format!("REPLAY STOPPED: {}", err)));
+                return;
+            }
+        }
+    });
+    Ok(())
+}
+
+// Sends every frame, sleeping out the recorded gaps divided by `speed`. Returns how many were sent.
+fn play(reader: impl BufRead, tx: &Sender<MonitorEvent>, speed: f64) -> Result<usize> {
+    let started = Instant::now();
+    let mut sent = 0;
+    for (n, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let FrameIn { at_ms, mut stats } = serde_json::from_str(&line).with_context(|| format!("line {}", n + 1))?;
+        let due = replay_delay(at_ms, speed).with_context(|| format!("line {}", n + 1))?;
+        if let Some(wait) = due.checked_sub(started.elapsed()) {
+            thread::sleep(wait);
+        }
+        stats.timestamp = Instant::now();
+        if tx.send(MonitorEvent::Stats(Box::new(stats))).is_err() {
+            break; // UI has gone away
+        }
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+// When a frame recorded `at_ms` into the recording is due, from the start of playback
+fn replay_delay(at_ms: u64, speed: f64) -> Result<Duration> {
+    match Duration::try_from_secs_f64(at_ms as f64 / 1000.0 / speed) {
+        Ok(due) => Ok(due),
+        Err(_) => bail!("timestamp {}ms is out of range at {}x", at_ms, speed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recording_round_trips_at_chart_cadence() {
+        let path = std::env::temp_dir().join(format!("mk05-record-test-{}.jsonl", std::process::id()));
+        let mut recorder = Recorder::create(&path).unwrap();
+        let t0 = Instant::now();
+        for (offset_ms, cpu) in [(0, 10.0), (20, 99.0), (150, 30.0)] {
+            let mut stats = crate::app::tests::sample(&[cpu]);
+            stats.timestamp = t0 + Duration::from_millis(offset_ms);
+            recorder.write(&stats).unwrap();
+        }
+        drop(recorder);
+
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let sent = play(BufReader::new(File::open(&path).unwrap()), &tx, 10.0).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(sent, 2, "the sample 20ms after the first is inside the record interval");
+        let cpus: Vec<f32> = rx.try_iter().filter_map(|e| match e {
+            MonitorEvent::Stats(s) => Some(s.cpu_usage[0]),
+            _ => None,
+        }).collect();
+        assert_eq!(cpus, vec![10.0, 30.0]);
+
+        let bad = "{\"at_ms\": 0, \"stats\": {}}\n";
+        assert!(play(BufReader::new(bad.as_bytes()), &tx, 1.0).is_err());
+    }
+
+    #[test]
+    fn replay_delay_scales_with_speed_and_rejects_overflow() {
+        let secs = |at_ms, speed| replay_delay(at_ms, speed).unwrap().as_secs_f64();
+        assert!((secs(150, 10.0) - 0.015).abs() < 1e-9, "150ms of recording at 10x");
+        assert!((secs(150, 0.5) - 0.3).abs() < 1e-9);
+        assert_eq!(secs(0, 1e-300), 0.0);
+        assert!(replay_delay(u64::MAX, 1e-300).is_err());
+    }
+}
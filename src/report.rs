@@ -0,0 +1,150 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+use anyhow::{Context, Result};
+use crate::app::App;
+use crate::monitor::SystemStats;
+use crate::units::{format_bytes, format_speed, UnitMode};
+
+const SNAPSHOT_PROCESSES: usize = 5;
+
+// Writes a plain-text snapshot of the current state to the working directory
+// and returns the file name. Built from `App`, not the terminal buffer, so it
+// reads the same regardless of window size or which view is active.
+pub fn write(app: &App) -> Result<PathBuf> {
+    let now = chrono::Local::now();
+    let path = PathBuf::from(format!("mk05-report-{}.txt", now.format("%Y%m%d-%H%M%S")));
+    fs::write(&path, render(app, &now.format("%Y-%m-%d %H:%M:%S").to_string()))
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+// --snapshot: the essentials of a single sample, printed to stdout for scripts
+pub fn snapshot(stats: &SystemStats, units: UnitMode) -> String {
+    let mut out = String::new();
+    let pct = |used: u64, total: u64| if total > 0 { used as f64 / total as f64 * 100.0 } else { 0.0 };
+    let model = stats.cpu_label().map(|m| format!("  {}", m)).unwrap_or_default();
+    let _ = writeln!(out, "CPU   {:>5.1}%{}", stats.total_cpu_usage, model);
+    let _ = writeln!(out, "RAM   {:>5.1}%  {} / {}", pct(stats.ram_used, stats.ram_total),
+        format_bytes(stats.ram_used, units), format_bytes(stats.ram_total, units));
+    let _ = writeln!(out, "LOAD  {:.2} {:.2} {:.2}", stats.load_avg.0, stats.load_avg.1, stats.load_avg.2);
+
+    let mut procs: Vec<_> = stats.processes.iter().flat_map(|list| list.iter()).collect();
+    procs.sort_by(|a, b| b.cpu.total_cmp(&a.cpu));
+    let _ = writeln!(out, "\n{:<8} {:<24} {:>6} {:>10}", "PID", "NAME", "CPU%", "MEM");
+    for p in procs.iter().take(SNAPSHOT_PROCESSES) {
+        let _ = writeln!(out, "{:<8} {:<24} {:>6.1} {:>10}", p.pid, p.name.chars().take(24).collect::<String>(), p.cpu, format_bytes(p.mem, units));
+    }
+
+    let _ = writeln!(out);
+    for disk in &stats.disks {
+        let _ = writeln!(out, "{:<20} {:<16} {:>5.1}%  {} / {}", disk.name, disk.mount, pct(disk.used, disk.total),
+            format_bytes(disk.used, units), format_bytes(disk.total, units));
+    }
+    out
+}
+
+fn render(app: &App, timestamp: &str) -> String {
+    let mut out = String::new();
+    let hostname = sysinfo::System::host_name().unwrap_or_else(|| "Unknown".to_string());
+    let _ = writeln!(out, "OMNI-MONITOR REPORT");
+    let _ = writeln!(out, "Host: {}    Taken: {}", hostname, timestamp);
+
+    let Some(stats) = &app.last_stats else {
+        let _ = writeln!(out, "\nNo sample collected yet.");
+        return out;
+    };
+    let pct = |used: u64, total: u64| if total > 0 { used as f64 / total as f64 * 100.0 } else { 0.0 };
+    let _ = writeln!(out, "Uptime: {:02}h {:02}m    Load: {:.2} {:.2} {:.2}",
+        stats.uptime / 3600, (stats.uptime % 3600) / 60, stats.load_avg.0, stats.load_avg.1, stats.load_avg.2);
+
+    let _ = writeln!(out, "\n== SYSTEM ==");
+    if let Some(cpu) = stats.cpu_label() {
+        let _ = writeln!(out, "MODEL {}", cpu);
+    }
+    let _ = writeln!(out, "CPU   {:>5.1}%", stats.total_cpu_usage);
+    let _ = writeln!(out, "RAM   {:>5.1}%  {} / {}", pct(stats.ram_used, stats.ram_total),
+        format_bytes(stats.ram_used, app.units), format_bytes(stats.ram_total, app.units));
+    let (_, cache, _) = stats.ram_breakdown();
+    let _ = writeln!(out, "      cache {}  available {}  free {}", format_bytes(cache, app.units),
+        format_bytes(stats.ram_available, app.units), format_bytes(stats.ram_free, app.units));
+    let _ = writeln!(out, "SWAP  {:>5.1}%  {} / {}  in {:.0} out {:.0} pages/s", pct(stats.swap_used, stats.swap_total),
+        format_bytes(stats.swap_used, app.units), format_bytes(stats.swap_total, app.units), stats.swap_in_rate, stats.swap_out_rate);
+    let _ = writeln!(out, "NET   ↓ {}/s  ↑ {}/s  (session ↓ {} ↑ {})",
+        format_speed(stats.rx_speed as f64, app.units), format_speed(stats.tx_speed as f64, app.units),
+        format_bytes(app.net_session_rx, app.units), format_bytes(app.net_session_tx, app.units));
+    let _ = writeln!(out, "SESSION {}  peak CPU {:.1}%  peak RAM {:.1}%", app.session_duration_label(), app.peak_cpu, app.peak_ram);
+    let cores: Vec<String> = stats.cpu_ids.iter().zip(&stats.cpu_usage).map(|(id, v)| format!("{}:{:.0}%", id, v)).collect();
+    let _ = writeln!(out, "CORES {}", cores.join(" "));
+
+    let _ = writeln!(out, "\n== DISKS ==");
+    for (i, disk) in app.disks.iter().enumerate() {
+        let name = &disk.name;
+        let io = match app.disk_io.get(i) {
+            Some(io) => format!("  R {}/s  W {}/s", format_speed(io.read as f64, app.units), format_speed(io.write as f64, app.units)),
+            None => String::new(),
+        };
+        let temp = match app.disk_health.iter().find(|(n, _)| n == name) {
+            Some((_, h)) => format!("  {}{}", app.temp_unit.format(h.temp as f64), if h.warning { " (HOT)" } else { "" }),
+            None => String::new(),
+        };
+        let _ = writeln!(out, "{:<20} {:<16} {:<8} {:>5.1}%  {} / {}{}{}", name, disk.mount, disk.fs, pct(disk.used, disk.total),
+            format_bytes(disk.used, app.units), format_bytes(disk.total, app.units), io, temp);
+    }
+
+    let _ = writeln!(out, "\n== TEMPERATURES ==");
+    if app.temps.is_empty() {
+        let _ = writeln!(out, "No sensors");
+    }
+    for temp in &app.temps {
+        let _ = writeln!(out, "{:<30} {:>5.1}{}", temp.name, app.temp_unit.convert(temp.celsius as f64), app.temp_unit.suffix());
+    }
+
+    let scope = match app.process_limit {
+        Some(n) => format!("top {}", n),
+        None => "all".to_string(),
+    };
+    let _ = writeln!(out, "\n== PROCESSES ({} by {}) ==", scope, format!("{:?}", app.process_sort_key).to_uppercase());
+    let _ = writeln!(out, "{:<8} {:<24} {:>6} {:>10} {:>12}", "PID", "NAME", "CPU%", "MEM", "DISK/s");
+    for p in app.processes.iter().take(app.listed_processes()) {
+        let _ = writeln!(out, "{:<8} {:<24} {:>6.1} {:>10} {:>12}", p.pid, p.name.chars().take(24).collect::<String>(), app.process_cpu(p.cpu),
+            format_bytes(p.mem, app.units), format_speed((p.disk_read + p.disk_write) as f64, app.units));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use super::*;
+    use crate::app::tests::{proc, sample};
+    use crate::monitor::DiskInfo;
+
+    #[test]
+    fn snapshot_lists_the_busiest_processes_and_every_disk() {
+        const GIB: u64 = 1 << 30;
+        let mut stats = sample(&[25.0, 75.0]);
+        (stats.cpu_brand, stats.physical_cores) = ("Test CPU".to_string(), Some(1));
+        (stats.ram_used, stats.ram_total) = (4 * GIB, 16 * GIB);
+        stats.load_avg = (1.5, 1.25, 0.75);
+        stats.processes = Some(Arc::new((1..=6).map(|pid| proc(pid, None, pid as f32 * 10.0)).collect()));
+        stats.disks = vec![DiskInfo { name: "/dev/sda1".into(), used: 50 * GIB, total: 200 * GIB, fs: "ext4".into(), mount: "/".into() }];
+
+        let out = snapshot(&stats, UnitMode::Binary);
+        let lines: Vec<String> = out.lines().map(|l| l.split_whitespace().collect::<Vec<_>>().join(" ")).collect();
+        assert_eq!(lines, [
+            "CPU 50.0% Test CPU (1C/2T)",
+            "RAM 25.0% 4.00 GiB / 16.00 GiB",
+            "LOAD 1.50 1.25 0.75",
+            "",
+            "PID NAME CPU% MEM",
+            "6 p6 60.0 1 B",
+            "5 p5 50.0 1 B",
+            "4 p4 40.0 1 B",
+            "3 p3 30.0 1 B",
+            "2 p2 20.0 1 B",
+            "",
+            "/dev/sda1 / 25.0% 50.00 GiB / 200.00 GiB",
+        ]);
+    }
+}
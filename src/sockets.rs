@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+
+// TCP sockets by state, IPv4 and IPv6 together
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ConnCounts {
+    pub established: usize,
+    pub time_wait: usize,
+    pub listen: usize,
+    pub other: usize, // SYN_SENT, CLOSE_WAIT, FIN_WAIT, ...
+}
+
+impl ConnCounts {
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn add(&mut self, state: u8) {
+        match state {
+            0x01 => self.established += 1,
+            0x06 => self.time_wait += 1,
+            0x0A => self.listen += 1,
+            _ => self.other += 1,
+        }
+    }
+}
+
+// None where the kernel tables aren't available
+#[cfg(target_os = "linux")]
+pub fn conn_counts() -> Option<ConnCounts> {
+    let mut counts = ConnCounts::default();
+    let mut found = false;
+    for path in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        if let Ok(table) = std::fs::read_to_string(path) {
+            found = true;
+            for state in socket_states(&table) {
+                counts.add(state);
+            }
+        }
+    }
+    found.then_some(counts)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn conn_counts() -> Option<ConnCounts> {
+    None
+}
+
+// The `st` column (hex) of each row in /proc/net/tcp{,6}, skipping the header
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn socket_states(table: &str) -> impl Iterator<Item = u8> + '_ {
+    table.lines().skip(1).filter_map(|line| u8::from_str_radix(line.split_whitespace().nth(3)?, 16).ok())
+}
+
+// One open TCP socket, for the connections panel
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Connection {
+    pub proto: String, // "tcp" or "tcp6"
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    pub state: String,    // Kernel state name ("LISTEN", "ESTABLISHED", ...)
+    pub pid: Option<u32>, // Owner; None when its /proc/<pid>/fd isn't readable to us
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn state_name(state: u8) -> &'static str {
+    match state {
+        0x01 => "ESTABLISHED",
+        0x02 => "SYN_SENT",
+        0x03 => "SYN_RECV",
+        0x04 => "FIN_WAIT1",
+        0x05 => "FIN_WAIT2",
+        0x06 => "TIME_WAIT",
+        0x07 => "CLOSE",
+        0x08 => "CLOSE_WAIT",
+        0x09 => "LAST_ACK",
+        0x0A => "LISTEN",
+        0x0B => "CLOSING",
+        _ => "UNKNOWN",
+    }
+}
+
+// Listening sockets first, then by local port. Walks every process's fd table to
+// find owners, so it's only called while the panel is open.
+#[cfg(target_os = "linux")]
+pub fn connections() -> Vec<Connection> {
+    let owners = socket_owners();
+    let mut list: Vec<Connection> = [("/proc/net/tcp", "tcp"), ("/proc/net/tcp6", "tcp6")].iter()
+        .filter_map(|(path, proto)| Some((std::fs::read_to_string(path).ok()?, *proto)))
+        .flat_map(|(table, proto)| parse_table(&table, proto, &owners))
+        .collect();
+    list.sort_by_key(|c| (c.state != "LISTEN", c.local.port(), c.remote.port()));
+    list
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn connections() -> Vec<Connection> {
+    Vec::new()
+}
+
+// Socket inode -> PID, from the "socket:[inode]" links under /proc/<pid>/fd
+#[cfg(target_os = "linux")]
+fn socket_owners() -> HashMap<u64, u32> {
+    let mut owners = HashMap::new();
+    let Ok(procs) = std::fs::read_dir("/proc") else { return owners };
+    for entry in procs.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse::<u32>().ok()) else { continue };
+        let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else { continue };
+        for fd in fds.flatten() {
+            let Ok(target) = std::fs::read_link(fd.path()) else { continue };
+            if let Some(inode) = target.to_str().and_then(|t| t.strip_prefix("socket:[")?.strip_suffix(']')?.parse().ok()) {
+                owners.insert(inode, pid);
+            }
+        }
+    }
+    owners
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_table(table: &str, proto: &str, owners: &HashMap<u64, u32>) -> Vec<Connection> {
+    table.lines().skip(1).filter_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let inode: u64 = fields.get(9)?.parse().ok()?;
+        Some(Connection {
+            proto: proto.to_string(),
+            local: parse_addr(fields.get(1)?)?,
+            remote: parse_addr(fields.get(2)?)?,
+            state: state_name(u8::from_str_radix(fields.get(3)?, 16).ok()?).to_string(),
+            pid: owners.get(&inode).copied(),
+        })
+    }).collect()
+}
+
+// "0100007F:1F90" -> 127.0.0.1:8080. The kernel prints the address as host-order
+// 32-bit words (one for IPv4, four for IPv6); the port is plain hex.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_addr(field: &str) -> Option<SocketAddr> {
+    let (addr, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let words: Vec<u32> = (0..addr.len() / 8)
+        .map(|i| u32::from_str_radix(&addr[i * 8..i * 8 + 8], 16))
+        .collect::<Result<_, _>>().ok()?;
+    let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_ne_bytes()).collect();
+    let ip = match bytes.len() {
+        4 => IpAddr::from(<[u8; 4]>::try_from(bytes).ok()?),
+        16 => IpAddr::from(<[u8; 16]>::try_from(bytes).ok()?),
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_states_from_proc_table() {
+        let table = "  sl  local_address rem_address   st tx_queue rx_queue\n\
+            0: 00000000:07E8 00000000:0000 0A 00000000:00000000\n\
+            1: 0100007F:BC8F 0100007F:1F90 01 00000000:00000000\n\
+            2: 0100007F:BC90 0100007F:1F90 01 00000000:00000000\n\
+            3: 0100007F:BC91 0100007F:1F90 06 00000000:00000000\n\
+            4: 0100007F:BC92 0100007F:1F90 08 00000000:00000000\n";
+        let mut counts = ConnCounts::default();
+        socket_states(table).for_each(|s| counts.add(s));
+        assert_eq!(counts, ConnCounts { established: 2, time_wait: 1, listen: 1, other: 1 });
+    }
+
+    #[test]
+    fn parses_connections_with_owners() {
+        let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n\
+            0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 4242\n\
+            1: 0100007F:BC8F 0100007F:1F90 01 00000000:00000000 00:00000000 00000000  1000        0 4343\n";
+        let owners = HashMap::from([(4242, 7)]);
+        let list = parse_table(table, "tcp", &owners);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].local, "127.0.0.1:8080".parse().unwrap());
+        assert_eq!((list[0].state.as_str(), list[0].pid), ("LISTEN", Some(7)));
+        assert_eq!((list[1].remote.port(), list[1].state.as_str(), list[1].pid), (8080, "ESTABLISHED", None));
+        assert_eq!(parse_addr("00000000000000000000000001000000:0016"), Some("[::1]:22".parse().unwrap()));
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::app::{App, DisplayMode, GroupKey, ProcessSortKey};
+use crate::theme;
+
+// View preferences remembered between runs (~/.cache/mk05/state.json).
+// Unknown or missing fields fall back to defaults so old files keep loading.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewState {
+    pub sort_key: ProcessSortKey,
+    pub sort_ascending: bool,
+    pub tree_view: bool,
+    pub grouped: bool,
+    pub group_key: GroupKey,
+    pub irix_mode: bool,
+    pub smoothing_enabled: bool,
+    pub chart_fill: bool,
+    pub theme: String,
+    pub accent: Option<String>, // None: the theme's own accent
+    pub display_mode: DisplayMode,
+}
+
+impl Default for ViewState {
+    fn default() -> Self {
+        Self {
+            sort_key: ProcessSortKey::Cpu,
+            sort_ascending: false,
+            tree_view: false,
+            grouped: false,
+            group_key: GroupKey::Name,
+            irix_mode: true,
+            smoothing_enabled: false,
+            chart_fill: false,
+            theme: theme::NEON.name.to_string(),
+            accent: None,
+            display_mode: DisplayMode::Full,
+        }
+    }
+}
+
+impl ViewState {
+    pub fn path() -> Option<PathBuf> {
+        let cache = std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+        Some(cache.join("mk05").join("state.json"))
+    }
+
+    // Missing or malformed files just mean "use the defaults"
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = Self::path() else { return Ok(()) };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn capture(app: &App) -> Self {
+        Self {
+            sort_key: app.process_sort_key,
+            sort_ascending: app.process_sort_ascending,
+            tree_view: app.process_tree_view,
+            grouped: app.process_grouped,
+            group_key: app.group_key,
+            irix_mode: app.irix_mode,
+            smoothing_enabled: app.smoothing_enabled,
+            chart_fill: app.chart_fill,
+            theme: app.theme().name.to_string(),
+            accent: app.accent_index.map(|i| theme::ACCENTS[i].name.to_string()),
+            display_mode: app.display_mode,
+        }
+    }
+
+    pub fn apply(&self, app: &mut App) {
+        app.process_sort_key = self.sort_key;
+        app.process_sort_ascending = self.sort_ascending;
+        app.process_tree_view = self.tree_view;
+        app.process_grouped = self.grouped && !self.tree_view;
+        app.group_key = self.group_key;
+        app.irix_mode = self.irix_mode;
+        app.smoothing_enabled = self.smoothing_enabled;
+        app.chart_fill = self.chart_fill;
+        app.theme_index = theme::index_of(&self.theme).unwrap_or(0);
+        app.accent_index = self.accent.as_deref().and_then(theme::accent_index_of);
+        app.display_mode = self.display_mode;
+    }
+}
+
+// Bump whenever the fields below change meaning; older files are then discarded
+pub const HISTORY_VERSION: u32 = 1;
+
+type Series = VecDeque<(f64, f64)>;
+
+// Chart history saved with --history-file so a restart doesn't blank the trends.
+// Only the raw series are stored; the smoothed ones are recomputed on load.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistorySnapshot {
+    pub version: u32,
+    pub chart_tick_count: f64,
+    pub cpu: Series,
+    pub cpu_min: Series,
+    pub cpu_max: Series,
+    pub ram: Series,
+    pub net_rx: Series,
+    pub net_tx: Series,
+    pub temp: Series,
+    #[serde(default)] // Added without a version bump: older files just start it empty
+    pub cpu_stack: [Series; 4],
+}
+
+impl HistorySnapshot {
+    // Written to a temporary file first so a crash mid-write can't leave a truncated file
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(self)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    // Ok(None) for a missing file, or one that doesn't parse or has another version
+    pub fn load(path: &Path) -> anyhow::Result<Option<Self>> {
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(serde_json::from_slice::<Self>(&data).ok().filter(|h| h.version == HISTORY_VERSION))
+    }
+}
@@ -0,0 +1,407 @@
+use anyhow::{bail, Result};
+use ratatui::style::Color;
+
+// Every color the UI draws with. Widgets take the active theme instead of
+// referencing fixed constants so the palette can be switched at runtime.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub name: &'static str,
+    pub bg: Color,
+    pub panel_bg: Color,
+    pub bar_bg: Color,      // Status bar and footer
+    pub row_alt_bg: Color,  // Zebra stripe in the process table
+    pub border: Color,
+    pub accent_main: Color,
+    pub accent_sec: Color,
+    pub accent_warn: Color,
+    pub accent_crit: Color,
+    pub accent_good: Color, // Battery charging
+    pub text_dim: Color,
+    pub text_lite: Color,
+    pub gauge_dim: Color,   // Swap gauge
+    pub net_rx: Color,
+    pub net_tx: Color,
+    pub heatmap: [Color; 6], // Idle -> saturated
+    pub heat_buckets: [u8; 5], // Inclusive upper load of each heatmap color but the last
+    pub plain: bool,         // No colors at all; widgets that rely on color need another cue
+}
+
+pub const NEON: Theme = Theme {
+    name: "neon",
+    bg: Color::Rgb(15, 17, 26),         // Deep Night Blue
+    panel_bg: Color::Rgb(15, 17, 26),
+    bar_bg: Color::Rgb(10, 12, 20),
+    row_alt_bg: Color::Rgb(20, 22, 35),
+    border: Color::Rgb(80, 80, 100),    // Steel Grey
+    accent_main: Color::Rgb(0, 255, 255), // Cyan
+    accent_sec: Color::Rgb(180, 0, 255),  // Purple
+    accent_warn: Color::Rgb(255, 180, 0), // Amber
+    accent_crit: Color::Rgb(255, 50, 80), // Red
+    accent_good: Color::Green,
+    text_dim: Color::Rgb(120, 130, 150),
+    text_lite: Color::Rgb(220, 230, 240),
+    gauge_dim: Color::DarkGray,
+    net_rx: Color::Green,
+    net_tx: Color::Red,
+    // Dark Blue -> Cyan -> Green -> Yellow -> Red
+    heatmap: [
+        Color::Rgb(15, 20, 30),
+        Color::Rgb(0, 50, 100),
+        Color::Rgb(0, 150, 150),
+        Color::Rgb(0, 255, 100),
+        Color::Rgb(200, 200, 0),
+        Color::Rgb(255, 0, 50),
+    ],
+    heat_buckets: DEFAULT_HEAT_BUCKETS,
+    plain: false,
+};
+
+pub const MONO: Theme = Theme {
+    name: "mono",
+    bg: Color::Rgb(10, 10, 10),
+    panel_bg: Color::Rgb(10, 10, 10),
+    bar_bg: Color::Rgb(30, 30, 30),
+    row_alt_bg: Color::Rgb(22, 22, 22),
+    border: Color::Rgb(90, 90, 90),
+    accent_main: Color::Rgb(230, 230, 230),
+    accent_sec: Color::Rgb(170, 170, 170),
+    accent_warn: Color::Rgb(255, 255, 255),
+    accent_crit: Color::Rgb(255, 255, 255),
+    accent_good: Color::Rgb(230, 230, 230),
+    text_dim: Color::Rgb(130, 130, 130),
+    text_lite: Color::Rgb(230, 230, 230),
+    gauge_dim: Color::Rgb(90, 90, 90),
+    net_rx: Color::Rgb(220, 220, 220),
+    net_tx: Color::Rgb(130, 130, 130),
+    heatmap: [
+        Color::Rgb(20, 20, 20),
+        Color::Rgb(60, 60, 60),
+        Color::Rgb(100, 100, 100),
+        Color::Rgb(150, 150, 150),
+        Color::Rgb(200, 200, 200),
+        Color::Rgb(250, 250, 250),
+    ],
+    heat_buckets: DEFAULT_HEAT_BUCKETS,
+    plain: false,
+};
+
+pub const LIGHT: Theme = Theme {
+    name: "light",
+    bg: Color::Rgb(250, 250, 245),
+    panel_bg: Color::Rgb(250, 250, 245),
+    bar_bg: Color::Rgb(225, 228, 235),
+    row_alt_bg: Color::Rgb(238, 240, 245),
+    border: Color::Rgb(170, 175, 190),
+    accent_main: Color::Rgb(0, 120, 170),
+    accent_sec: Color::Rgb(130, 40, 180),
+    accent_warn: Color::Rgb(200, 120, 0),
+    accent_crit: Color::Rgb(200, 30, 50),
+    accent_good: Color::Rgb(0, 140, 60),
+    text_dim: Color::Rgb(110, 115, 130),
+    text_lite: Color::Rgb(30, 35, 45),
+    gauge_dim: Color::Rgb(170, 170, 170),
+    net_rx: Color::Rgb(0, 140, 60),
+    net_tx: Color::Rgb(200, 30, 50),
+    heatmap: [
+        Color::Rgb(235, 238, 245),
+        Color::Rgb(180, 210, 235),
+        Color::Rgb(110, 180, 220),
+        Color::Rgb(90, 190, 120),
+        Color::Rgb(230, 190, 40),
+        Color::Rgb(210, 40, 60),
+    ],
+    heat_buckets: DEFAULT_HEAT_BUCKETS,
+    plain: false,
+};
+
+pub const THEMES: &[Theme] = &[NEON, MONO, LIGHT];
+
+pub fn index_of(name: &str) -> Option<usize> {
+    THEMES.iter().position(|t| t.name.eq_ignore_ascii_case(name))
+}
+
+// Accent presets cycled with 'T' on top of any theme: the main accent and the
+// status bar background, in a dark-background and a light-background variant
+#[derive(Debug, Clone, Copy)]
+pub struct Accent {
+    pub name: &'static str,
+    pub dark: (Color, Color),  // (accent_main, bar_bg)
+    pub light: (Color, Color),
+}
+
+pub const ACCENTS: &[Accent] = &[
+    Accent { name: "cyan", dark: (Color::Rgb(0, 255, 255), Color::Rgb(5, 25, 30)), light: (Color::Rgb(0, 120, 170), Color::Rgb(215, 235, 240)) },
+    Accent { name: "magenta", dark: (Color::Rgb(255, 60, 200), Color::Rgb(30, 8, 26)), light: (Color::Rgb(170, 20, 130), Color::Rgb(240, 220, 235)) },
+    Accent { name: "lime", dark: (Color::Rgb(150, 255, 0), Color::Rgb(16, 28, 4)), light: (Color::Rgb(60, 130, 0), Color::Rgb(225, 240, 210)) },
+    Accent { name: "orange", dark: (Color::Rgb(255, 140, 0), Color::Rgb(30, 18, 4)), light: (Color::Rgb(190, 90, 0), Color::Rgb(245, 230, 210)) },
+    Accent { name: "blue", dark: (Color::Rgb(80, 140, 255), Color::Rgb(10, 16, 36)), light: (Color::Rgb(30, 80, 200), Color::Rgb(215, 225, 245)) },
+];
+
+pub fn accent_index_of(name: &str) -> Option<usize> {
+    ACCENTS.iter().position(|a| a.name.eq_ignore_ascii_case(name))
+}
+
+// Load scale for the heatmap and usage gauges (--palette)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Palette {
+    #[default]
+    Classic, // The theme's own heatmap colors
+    Viridis, // Perceptually uniform dark purple -> yellow, no red/green contrast
+}
+
+impl Palette {
+    pub fn parse(name: &str) -> Result<Self> {
+        Ok(match name.to_lowercase().as_str() {
+            "classic" | "default" => Palette::Classic,
+            "viridis" => Palette::Viridis,
+            other => bail!("unknown palette '{}' (expected classic or viridis)", other),
+        })
+    }
+}
+
+// Stops sampled from matplotlib's viridis
+const VIRIDIS: [Color; 6] = [
+    Color::Rgb(68, 1, 84),
+    Color::Rgb(65, 68, 135),
+    Color::Rgb(42, 120, 142),
+    Color::Rgb(34, 168, 132),
+    Color::Rgb(122, 209, 81),
+    Color::Rgb(253, 231, 37),
+];
+
+pub const DEFAULT_HEAT_BUCKETS: [u8; 5] = [5, 20, 40, 60, 80];
+
+// --heatmap-buckets "5,20,40,60,80": five strictly increasing loads (0-100), the
+// inclusive top of each color but the saturated one
+pub fn parse_heat_buckets(spec: &str) -> Result<[u8; 5]> {
+    let values = spec.split(',').map(|v| {
+        let v = v.trim();
+        match v.parse::<u8>() {
+            Ok(n) if n <= 100 => Ok(n),
+            _ => bail!("invalid heatmap bucket '{}' (expected a load from 0 to 100)", v),
+        }
+    }).collect::<Result<Vec<u8>>>()?;
+    let Ok(buckets) = <[u8; 5]>::try_from(values) else {
+        bail!("--heatmap-buckets needs exactly 5 values, like {}", DEFAULT_HEAT_BUCKETS.map(|b| b.to_string()).join(","));
+    };
+    if !buckets.windows(2).all(|w| w[0] < w[1]) {
+        bail!("heatmap buckets must be strictly increasing: {}", spec);
+    }
+    Ok(buckets)
+}
+
+// --heatmap-colors: six names or #rrggbb values, idle -> saturated
+pub fn parse_heat_colors(spec: &str) -> Result<[Color; 6]> {
+    let colors = spec.split(',').map(|c| {
+        let c = c.trim();
+        c.parse::<Color>().map_err(|_| anyhow::anyhow!("invalid heatmap color '{}' (expected a name like red or #rrggbb)", c))
+    }).collect::<Result<Vec<Color>>>()?;
+    <[Color; 6]>::try_from(colors).map_err(|_| anyhow::anyhow!("--heatmap-colors needs exactly 6 colors, idle to saturated"))
+}
+
+// What the terminal can display, detected once at startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSupport {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    None, // NO_COLOR
+}
+
+impl ColorSupport {
+    // NO_COLOR (https://no-color.org) wins; otherwise trust COLORTERM, then TERM
+    pub fn detect() -> Self {
+        let var = |name: &str| std::env::var(name).unwrap_or_default().to_lowercase();
+        if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            return ColorSupport::None;
+        }
+        let colorterm = var("COLORTERM");
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorSupport::TrueColor;
+        }
+        let term = var("TERM");
+        if term == "dumb" {
+            ColorSupport::None
+        } else if term.contains("256color") {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+}
+
+// xterm's default RGB values for the 16 ANSI colors
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+// Levels of the 6x6x6 cube in the xterm 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+fn nearest_ansi16(rgb: (u8, u8, u8)) -> Color {
+    ANSI16.iter().min_by_key(|(_, v)| distance(rgb, *v)).map(|(c, _)| *c).unwrap_or(Color::Reset)
+}
+
+fn nearest_ansi256(rgb: (u8, u8, u8)) -> Color {
+    let level = |v: u8| (0..6).min_by_key(|&i| (CUBE_LEVELS[i] as i32 - v as i32).abs()).unwrap_or(0);
+    let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = (CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+
+    // Grayscale ramp 232..=255 covers 8..=238 in steps of 10
+    let avg = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let step = (avg.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + step * 10;
+
+    if distance(rgb, (gray, gray, gray)) < distance(rgb, cube) {
+        Color::Indexed(232 + step)
+    } else {
+        Color::Indexed(16 + 36 * r as u8 + 6 * g as u8 + b as u8)
+    }
+}
+
+impl Theme {
+    // Rgb themes only; named terminal colors count as dark
+    fn is_light(&self) -> bool {
+        matches!(self.bg, Color::Rgb(r, g, b) if (r as u32 + g as u32 + b as u32) / 3 >= 128)
+    }
+
+    pub fn with_accent(mut self, accent: Option<&Accent>) -> Self {
+        if let Some(accent) = accent {
+            (self.accent_main, self.bar_bg) = if self.is_light() { accent.light } else { accent.dark };
+        }
+        self
+    }
+
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        if palette == Palette::Viridis {
+            self.heatmap = VIRIDIS;
+        }
+        self
+    }
+
+    // --heatmap-buckets / --heatmap-colors, over whatever the palette chose
+    pub fn with_heat_scale(mut self, buckets: Option<[u8; 5]>, colors: Option<[Color; 6]>) -> Self {
+        self.heat_buckets = buckets.unwrap_or(self.heat_buckets);
+        self.heatmap = colors.unwrap_or(self.heatmap);
+        self
+    }
+
+    // Buckets a 0-100 load onto the six-step idle -> saturated scale
+    pub fn load_color(&self, load: u8) -> Color {
+        let bucket = self.heat_buckets.iter().position(|&top| load <= top).unwrap_or(self.heat_buckets.len());
+        self.heatmap[bucket]
+    }
+
+    // Maps every color down to what the terminal supports
+    pub fn degrade(mut self, support: ColorSupport) -> Self {
+        let map = |c: Color| match (support, c) {
+            (ColorSupport::TrueColor, c) => c,
+            (ColorSupport::None, _) => Color::Reset,
+            (ColorSupport::Ansi256, Color::Rgb(r, g, b)) => nearest_ansi256((r, g, b)),
+            (ColorSupport::Ansi16, Color::Rgb(r, g, b)) => nearest_ansi16((r, g, b)),
+            (_, c) => c,
+        };
+        for c in [
+            &mut self.bg, &mut self.panel_bg, &mut self.bar_bg, &mut self.row_alt_bg, &mut self.border,
+            &mut self.accent_main, &mut self.accent_sec, &mut self.accent_warn, &mut self.accent_crit,
+            &mut self.accent_good, &mut self.text_dim, &mut self.text_lite, &mut self.gauge_dim,
+            &mut self.net_rx, &mut self.net_tx,
+        ] {
+            *c = map(*c);
+        }
+        for c in &mut self.heatmap {
+            *c = map(*c);
+        }
+        self.plain = support == ColorSupport::None;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ansi16_picks_nearest_named_color() {
+        assert_eq!(nearest_ansi16((15, 17, 26)), Color::Black);
+        assert_eq!(nearest_ansi16((0, 255, 255)), Color::LightCyan);
+        assert_eq!(nearest_ansi16((255, 50, 80)), Color::LightRed);
+    }
+
+    #[test]
+    fn ansi256_uses_gray_ramp_for_neutral_colors() {
+        assert_eq!(nearest_ansi256((128, 128, 128)), Color::Indexed(244));
+        assert_eq!(nearest_ansi256((0, 255, 255)), Color::Indexed(51));
+    }
+
+    #[test]
+    fn degraded_themes_contain_no_rgb() {
+        for theme in THEMES {
+            for support in [ColorSupport::Ansi256, ColorSupport::Ansi16, ColorSupport::None] {
+                let t = theme.degrade(support);
+                let colors = [t.bg, t.panel_bg, t.bar_bg, t.border, t.accent_main, t.accent_crit, t.text_lite, t.net_rx];
+                assert!(colors.iter().chain(t.heatmap.iter()).all(|c| !matches!(c, Color::Rgb(..))));
+            }
+        }
+        assert!(NEON.degrade(ColorSupport::None).plain);
+    }
+
+    #[test]
+    fn accents_follow_the_theme_background() {
+        let magenta = &ACCENTS[accent_index_of("magenta").unwrap()];
+        assert_eq!(NEON.with_accent(Some(magenta)).accent_main, magenta.dark.0);
+        assert_eq!(LIGHT.with_accent(Some(magenta)).bar_bg, magenta.light.1);
+        assert_eq!(NEON.with_accent(None).accent_main, NEON.accent_main);
+    }
+
+    #[test]
+    fn viridis_replaces_only_the_load_scale() {
+        let t = LIGHT.with_palette(Palette::Viridis);
+        assert_eq!(t.heatmap, VIRIDIS);
+        assert_eq!(t.accent_main, LIGHT.accent_main);
+        assert_eq!(LIGHT.with_palette(Palette::Classic).heatmap, LIGHT.heatmap);
+        assert_eq!(t.load_color(0), VIRIDIS[0]);
+        assert_eq!(t.load_color(50), VIRIDIS[3]);
+        assert_eq!(t.load_color(100), VIRIDIS[5]);
+        assert!(Palette::parse("rainbow").is_err());
+    }
+
+    #[test]
+    fn heat_buckets_parse_and_move_the_color_steps() {
+        let buckets = parse_heat_buckets("5, 15, 30, 40, 50").unwrap();
+        let t = NEON.with_heat_scale(Some(buckets), None);
+        assert_eq!(t.load_color(50), NEON.heatmap[4]);
+        assert_eq!(t.load_color(51), NEON.heatmap[5]); // Red from 51% on
+        assert_eq!(NEON.load_color(80), NEON.heatmap[4]);
+
+        assert!(parse_heat_buckets("5,20,20,60,80").is_err()); // Not increasing
+        assert!(parse_heat_buckets("5,20,40,60").is_err());
+        assert!(parse_heat_buckets("5,20,40,60,101").is_err());
+
+        let colors = parse_heat_colors("black,blue,#00ff00,yellow,#ff8800,red").unwrap();
+        assert_eq!(colors[2], Color::Rgb(0, 255, 0));
+        assert_eq!(NEON.with_heat_scale(None, Some(colors)).load_color(100), Color::Red);
+        assert!(parse_heat_colors("red,blue").is_err());
+        assert!(parse_heat_colors("black,blue,green,yellow,orange?,red").is_err());
+    }
+}
@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+use std::fs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoreType {
+    Performance,
+    Efficiency,
+}
+
+// Core type per core id, or empty when the CPU isn't hybrid or the platform
+// doesn't say. Read once at startup; topology doesn't change at runtime.
+pub fn core_types() -> HashMap<usize, CoreType> {
+    let types = intel_hybrid().unwrap_or_else(cpu_capacity);
+    // A single group carries no information, keep the flat layout
+    if types.values().all(|t| *t == CoreType::Performance) || types.values().all(|t| *t == CoreType::Efficiency) {
+        return HashMap::new();
+    }
+    types
+}
+
+// Intel 12th gen+: the hybrid PMUs list which CPUs belong to each core type
+fn intel_hybrid() -> Option<HashMap<usize, CoreType>> {
+    let p = fs::read_to_string("/sys/devices/cpu_core/cpus").ok()?;
+    let e = fs::read_to_string("/sys/devices/cpu_atom/cpus").ok()?;
+    let mut types = HashMap::new();
+    types.extend(parse_cpu_list(&p).into_iter().map(|id| (id, CoreType::Performance)));
+    types.extend(parse_cpu_list(&e).into_iter().map(|id| (id, CoreType::Efficiency)));
+    Some(types)
+}
+
+// ARM big.LITTLE / Apple Silicon under Linux: cores below the highest capacity are efficiency cores
+fn cpu_capacity() -> HashMap<usize, CoreType> {
+    let Ok(entries) = fs::read_dir("/sys/devices/system/cpu") else { return HashMap::new() };
+    let capacities: Vec<(usize, u32)> = entries.flatten().filter_map(|entry| {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let id = name.strip_prefix("cpu")?.parse().ok()?;
+        let capacity = fs::read_to_string(entry.path().join("cpu_capacity")).ok()?.trim().parse().ok()?;
+        Some((id, capacity))
+    }).collect();
+    let Some(max) = capacities.iter().map(|(_, c)| *c).max() else { return HashMap::new() };
+    capacities.into_iter()
+        .map(|(id, c)| (id, if c == max { CoreType::Performance } else { CoreType::Efficiency }))
+        .collect()
+}
+
+// Kernel cpu list format: "0-7,16,18-19"
+fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.trim().split(',').filter(|s| !s.is_empty()).flat_map(|range| {
+        let (start, end) = match range.split_once('-') {
+            Some((a, b)) => (a.parse().ok(), b.parse().ok()),
+            None => (range.parse().ok(), range.parse().ok()),
+        };
+        match (start, end) {
+            (Some(a), Some(b)) if a <= b => (a..=b).collect(),
+            _ => Vec::new(),
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_kernel_cpu_lists() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list(""), Vec::<usize>::new());
+        assert_eq!(parse_cpu_list("5-2,x"), Vec::<usize>::new());
+    }
+}
@@ -1,80 +1,634 @@
+use std::time::Instant;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect, Alignment},
+    layout::{Constraint, Direction, Flex, Layout, Rect, Alignment},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{
         canvas::{Canvas, Rectangle},
-        Axis, Block, Borders, BorderType, Chart, Dataset, Gauge, 
-        GraphType, Paragraph, Row, Table, TableState
+        Axis, Block, Borders, BorderType, Chart, Clear, Dataset, Gauge,
+        GraphType, Paragraph, Row, Sparkline, Table, TableState, Wrap
     },
     Frame,
     symbols,
 };
-use crate::app::App;
+use crate::alerts::AlertMetric;
+use crate::app::{App, ChartId, Column, DisplayMode, FocusPanel, GroupKey, KillMenu, ProcessGroup, ProcessSortKey, ProcessTableLayout, RunningStats, TempView, STACKED_SECTIONS, TEMP_OVERLAY_COUNT};
+use crate::battery::{BatteryInfo, ChargeState};
+use crate::keys::KEY_BINDINGS;
+use crate::monitor::{KillSignal, ProcessInfo, SystemStats};
+use crate::theme::{Palette, Theme};
+use crate::topology::CoreType;
+use crate::units::{format_bytes, format_bytes_compact, format_count, format_speed};
+use std::collections::VecDeque;
 
-// --- PRO THEME PALETTE ---
-const C_BG: Color = Color::Rgb(15, 17, 26);         // Deep Night Blue
-const C_PANEL_BG: Color = Color::Rgb(15, 17, 26);
-const C_BORDER: Color = Color::Rgb(80, 80, 100);    // Steel Grey
-const C_BORDER_FOCUS: Color = Color::Rgb(0, 200, 255);
+// --- HELPER ---
+fn now_millis() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
 
-const C_ACCENT_MAIN: Color = Color::Rgb(0, 255, 255); // Cyan
-const C_ACCENT_SEC: Color = Color::Rgb(180, 0, 255);  // Purple
-const C_ACCENT_WARN: Color = Color::Rgb(255, 180, 0); // Amber
-const C_ACCENT_CRIT: Color = Color::Rgb(255, 50, 80); // Red
-const C_TEXT_DIM: Color = Color::Rgb(120, 130, 150);
-const C_TEXT_LITE: Color = Color::Rgb(220, 230, 240);
+// Shared blink phase so every alerting element flashes in sync
+fn flash_on() -> bool {
+    (now_millis() / 500).is_multiple_of(2)
+}
 
-// --- HELPER ---
-fn format_speed(bytes: f64) -> String {
-    if bytes < 1024.0 { format!("{:.0} B", bytes) }
-    else if bytes < 1024.0 * 1024.0 { format!("{:.1} K", bytes / 1024.0) }
-    else { format!("{:.1} M", bytes / 1024.0 / 1024.0) }
+// Border color for a panel whose metric may be alerting: flashes red at ~1Hz while firing
+fn alert_color(app: &App, theme: &Theme, metrics: &[AlertMetric], normal: Color) -> Color {
+    if flash_on() && metrics.iter().any(|m| app.alerts.is_firing(*m)) { theme.accent_crit } else { normal }
 }
 
-fn block_pro(title: &str, border_color: Color) -> Block {
+fn block_pro<'a>(title: &'a str, border_color: Color, theme: &Theme) -> Block<'a> {
     Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(border_color))
         .title(Span::styled(format!(" {} ", title), Style::default().fg(border_color).add_modifier(Modifier::BOLD)))
         .title_alignment(Alignment::Center)
-        .style(Style::default().bg(C_PANEL_BG))
+        .style(Style::default().bg(theme.panel_bg))
 }
 
 pub fn draw(f: &mut Frame, app: &App) {
+    let theme = &app.theme();
+
     // Global Background
-    f.render_widget(Block::default().style(Style::default().bg(C_BG)), f.area());
+    f.render_widget(Block::default().style(Style::default().bg(theme.bg)), f.area());
 
-    // Main Layout: Header vs Body
+    // Main Layout: Header vs Body vs Footer. Compact mode only gives up the footer
+    // row for a message, since saving rows is what it's for.
+    let footer_msg = app.active_footer_message();
+    let show_footer = app.display_mode == DisplayMode::Full || footer_msg.is_some() || app.alerts.active().next().is_some() || app.show_stats;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(1),  // Compact Status Bar
             Constraint::Min(0),     // Content
+            Constraint::Length(if show_footer { 1 } else { 0 }), // Footer
         ].as_ref())
         .split(f.area());
 
-    draw_status_bar(f, app, chunks[0]);
-    draw_content_grid(f, app, chunks[1]);
+    draw_status_bar(f, app, theme, chunks[0]);
+    match app.display_mode {
+        DisplayMode::Full => draw_content_grid(f, app, theme, chunks[1]),
+        DisplayMode::Compact => draw_compact(f, app, theme, chunks[1]),
+    }
+    if show_footer {
+        draw_footer(f, app, theme, footer_msg, chunks[2]);
+    }
+
+    if app.monitor_disconnected {
+        draw_stale_banner(f, theme, "⚠ MONITOR DISCONNECTED · data below is stale", chunks[1]);
+    } else if let Some(silent) = app.stale_for(Instant::now()) {
+        let msg = format!("⚠ DATA STALE · monitor not responding (no sample for {}s)", silent.as_secs());
+        draw_stale_banner(f, theme, &msg, chunks[1]);
+    }
+
+    // Overlays
+    if app.show_diagnostics {
+        draw_diagnostics(f, app, theme, chunks[1]);
+    }
+    if let Some(pid) = app.show_process_detail {
+        draw_process_detail(f, app, theme, pid, chunks[1]);
+    }
+    if app.show_help {
+        draw_help(f, theme, chunks[1]);
+    }
+    if app.show_sockets {
+        draw_sockets(f, app, theme, chunks[1]);
+    }
+    if app.show_storage {
+        draw_storage(f, app, theme, chunks[1]);
+    }
+    if let Some(menu) = &app.kill_menu {
+        draw_kill_menu(f, theme, menu, chunks[1]);
+    }
+}
+
+// Full-width bar across the top of the content so frozen numbers aren't mistaken for live ones
+fn draw_stale_banner(f: &mut Frame, theme: &Theme, msg: &str, area: Rect) {
+    let bar = Rect { height: 1.min(area.height), ..area };
+    let style = Style::default().fg(theme.bg).bg(theme.accent_crit).add_modifier(Modifier::BOLD);
+    f.render_widget(Clear, bar);
+    f.render_widget(Paragraph::new(msg.to_string()).alignment(Alignment::Center).style(style), bar);
+}
+
+// Small box in the top-right corner of the content. OVERHEAD is the monitor's slow
+// refresh and the time spent drawing one frame.
+fn draw_diagnostics(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let diag = &app.diagnostics;
+    let ms = |d: Option<std::time::Duration>| d.map(|d| format!("{:.1}ms", d.as_secs_f64() * 1000.0)).unwrap_or_else(|| "-".to_string());
+    let monitor = app.last_stats.as_ref().map(|s| format!("{:.1}ms", s.refresh_ms)).unwrap_or_else(|| "-".to_string());
+    let field = |label: &str, value: String| Line::from(vec![
+        Span::styled(format!("{:<10}", label), Style::default().fg(theme.text_dim)),
+        Span::styled(value, Style::default().fg(theme.text_lite)),
+    ]);
+    let lines = vec![
+        field("UI", format!("{:.1} fps", diag.fps)),
+        field("SAMPLES", format!("{:.0}/s", diag.samples_per_sec)),
+        field("INTERVAL", ms(diag.sample_interval)),
+        field("BACKLOG", diag.backlog.to_string()),
+        field("DRAINED", format!("{} / frame", diag.drained)),
+        field("WAKEUPS", format!("{:.0}/s", diag.wakeups_per_sec)),
+        field("OVERHEAD", format!("monitor {} / ui {}", monitor, ms(diag.render_time))),
+    ];
+    let (width, height) = (40, lines.len() as u16 + 2);
+    let popup = Rect {
+        x: area.right().saturating_sub(width),
+        y: area.y,
+        width: width.min(area.width),
+        height: height.min(area.height),
+    };
+    f.render_widget(Clear, popup);
+    f.render_widget(Paragraph::new(lines).block(block_pro("DIAGNOSTICS", theme.text_dim, theme)), popup);
+}
+
+fn draw_kill_menu(f: &mut Frame, theme: &Theme, menu: &KillMenu, area: Rect) {
+    let height = KillSignal::ALL.len() as u16 + 4;
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(40) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width: 40.min(area.width),
+        height: height.min(area.height),
+    };
+    f.render_widget(Clear, popup);
+
+    let mut lines: Vec<Line> = KillSignal::ALL.iter().enumerate().map(|(i, signal)| {
+        let style = if i == menu.selected {
+            Style::default().fg(theme.bg).bg(theme.accent_crit).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text_lite)
+        };
+        let marker = if i == menu.selected { "▶" } else { " " };
+        Line::from(Span::styled(format!("{} {:<8} {}", marker, signal.name(), signal.description()), style))
+    }).collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Enter send · Esc cancel", Style::default().fg(theme.text_dim))));
+
+    let title = format!("SIGNAL {} · {}", menu.pid, menu.name);
+    f.render_widget(Paragraph::new(lines).block(block_pro(&title, theme.accent_crit, theme)), popup);
+}
+
+// Open TCP sockets with their owning process, scrolled with j/k
+fn draw_sockets(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let popup = centered_rect(80, 70, area);
+    f.render_widget(Clear, popup);
+    let title = format!("CONNECTIONS · {} OPEN", app.sockets.len());
+    let border = if app.focused_panel == FocusPanel::Connections { theme.accent_warn } else { theme.border };
+    let block = block_pro(&title, border, theme);
+    app.socket_rows.set(block.inner(popup).height.saturating_sub(2) as usize); // Less header and its margin
+    if app.sockets.is_empty() {
+        let text = if cfg!(target_os = "linux") { "collecting…" } else { "Not supported on this platform" };
+        f.render_widget(Paragraph::new(text).style(Style::default().fg(theme.text_dim)).block(block), popup);
+        return;
+    }
+
+    let dim = Style::default().fg(theme.text_dim);
+    let header = Row::new(["PROTO", "LOCAL", "REMOTE", "STATE", "PID", "PROCESS"]).style(dim).bottom_margin(1);
+    let processes = &app.all_processes;
+    let rows = app.sockets.iter().map(|c| {
+        let owner = c.pid.and_then(|pid| processes.iter().find(|p| p.pid == pid)).map(|p| p.name.clone()).unwrap_or_else(|| "-".to_string());
+        let state = if c.state == "LISTEN" { Style::default().fg(theme.accent_good) } else { Style::default().fg(theme.text_lite) };
+        Row::new(vec![
+            ratatui::widgets::Cell::from(c.proto.clone()).style(dim),
+            ratatui::widgets::Cell::from(c.local.to_string()).style(Style::default().fg(theme.text_lite)),
+            ratatui::widgets::Cell::from(c.remote.to_string()).style(dim),
+            ratatui::widgets::Cell::from(c.state.clone()).style(state),
+            ratatui::widgets::Cell::from(c.pid.map(|p| p.to_string()).unwrap_or_else(|| "-".to_string())).style(dim),
+            ratatui::widgets::Cell::from(owner).style(Style::default().fg(theme.accent_main)),
+        ])
+    });
+    let widths = [Constraint::Length(5), Constraint::Min(22), Constraint::Min(22), Constraint::Length(11), Constraint::Length(7), Constraint::Min(10)];
+    let mut highlight = Style::default().bg(theme.border).add_modifier(Modifier::BOLD);
+    if theme.plain {
+        highlight = highlight.add_modifier(Modifier::REVERSED);
+    }
+    let table = Table::new(rows, widths).header(header).block(block).row_highlight_style(highlight);
+    let mut state = TableState::default();
+    state.select(Some(app.socket_scroll));
+    f.render_stateful_widget(table, popup, &mut state);
+}
+
+fn draw_storage(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let popup = centered_rect(80, 70, area);
+    f.render_widget(Clear, popup);
+    let title = format!("STORAGE · {} DISKS · SORT {} (s)", app.disks.len(), app.storage_sort.name());
+    let border = if app.focused_panel == FocusPanel::Storage { theme.accent_main } else { theme.border };
+    let block = block_pro(&title, border, theme);
+    app.storage_rows.set(block.inner(popup).height.saturating_sub(2) as usize); // Less header and its margin
+    if app.disks.is_empty() {
+        f.render_widget(Paragraph::new("No disks reported").style(Style::default().fg(theme.text_dim)).block(block), popup);
+        return;
+    }
+
+    let dim = Style::default().fg(theme.text_dim);
+    let header = Row::new(["MOUNT", "DEVICE", "FS", "USED", "TOTAL", "AVAIL", "USE%"]).style(dim).bottom_margin(1);
+    let rows = app.storage_disks().into_iter().map(|d| {
+        let pct = d.usage_pct();
+        let color = match pct {
+            Some(p) if p >= 90.0 => theme.accent_crit,
+            Some(p) if p >= 75.0 => theme.accent_warn,
+            _ => theme.accent_good,
+        };
+        Row::new(vec![
+            ratatui::widgets::Cell::from(d.mount.clone()).style(Style::default().fg(theme.text_lite)),
+            ratatui::widgets::Cell::from(d.name.clone()).style(dim),
+            ratatui::widgets::Cell::from(d.fs.clone()).style(dim),
+            ratatui::widgets::Cell::from(format_bytes(d.used, app.units)),
+            ratatui::widgets::Cell::from(format_bytes(d.total, app.units)),
+            ratatui::widgets::Cell::from(format_bytes(d.total.saturating_sub(d.used), app.units)),
+            ratatui::widgets::Cell::from(pct.map(|p| format!("{:.1}%", p)).unwrap_or_else(|| "-".to_string())).style(Style::default().fg(color)),
+        ])
+    });
+    let widths = [Constraint::Min(16), Constraint::Min(12), Constraint::Length(8), Constraint::Length(11), Constraint::Length(11), Constraint::Length(11), Constraint::Length(6)];
+    let mut highlight = Style::default().bg(theme.border).add_modifier(Modifier::BOLD);
+    if theme.plain {
+        highlight = highlight.add_modifier(Modifier::REVERSED);
+    }
+    let table = Table::new(rows, widths).header(header).block(block).row_highlight_style(highlight);
+    let mut state = TableState::default();
+    state.select(Some(app.storage_scroll));
+    f.render_stateful_widget(table, popup, &mut state);
+}
+
+fn draw_help(f: &mut Frame, theme: &Theme, area: Rect) {
+    let popup = centered_rect(50, 60, area);
+    f.render_widget(Clear, popup);
+
+    let lines: Vec<Line> = KEY_BINDINGS.iter().map(|b| Line::from(vec![
+        Span::styled(format!("{:>8}  ", b.label), Style::default().fg(theme.accent_warn).add_modifier(Modifier::BOLD)),
+        Span::styled(b.description, Style::default().fg(theme.text_lite)),
+    ])).collect();
+
+    let block = block_pro("KEYBINDINGS", theme.accent_main, theme);
+    f.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+fn draw_process_detail(f: &mut Frame, app: &App, theme: &Theme, pid: u32, area: Rect) {
+    let popup = centered_rect(60, 60, area);
+    f.render_widget(Clear, popup);
+
+    let group = app.process_grouped && !app.process_tree_view;
+    if let Some(group) = app.process_groups.iter().find(|g| group && g.pids.len() > 1 && g.pids.contains(&pid)) {
+        draw_group_detail(f, app, theme, group, popup);
+        return;
+    }
+    let Some(p) = app.processes.iter().find(|p| p.pid == pid) else {
+        let title = format!("PROCESS {}", pid);
+        let block = block_pro(&title, theme.accent_main, theme);
+        f.render_widget(Paragraph::new("Process has exited").block(block), popup);
+        return;
+    };
+
+    let started = chrono::DateTime::from_timestamp(p.start_time as i64, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let run_time = (chrono::Utc::now().timestamp() - p.start_time as i64).max(0) as u64;
+    let field = |label: &str, value: String| Line::from(vec![
+        Span::styled(format!("{:<10}", label), Style::default().fg(theme.text_dim)),
+        Span::styled(value, Style::default().fg(theme.text_lite)),
+    ]);
+
+    let lines = vec![
+        field("COMMAND", if p.cmd.is_empty() { p.name.clone() } else { p.cmd.clone() }),
+        field("EXE", p.exe.clone().unwrap_or_else(|| "-".to_string())),
+        field("CWD", p.cwd.clone().unwrap_or_else(|| "-".to_string())),
+        field("PID", format!("{}  (parent {})", p.pid, p.parent_pid.map(|pp| pp.to_string()).unwrap_or_else(|| "-".to_string()))),
+        field("USER", p.user.clone().unwrap_or_else(|| "-".to_string())),
+        field("STARTED", started),
+        field("RUN TIME", format_run_time(run_time)),
+        field("STATUS", p.status.clone()),
+        field("THREADS", p.threads.map(|t| t.to_string()).unwrap_or_else(|| "-".to_string())),
+        field("CPU", format!("{:.1}%", app.process_cpu(p.cpu))),
+        field("MEM", format_bytes(p.mem, app.units)),
+        field("DISK", format!("R {}  W {}", format_bytes(p.disk_read_total, app.units), format_bytes(p.disk_write_total, app.units))),
+    ];
+
+    let title = format!("PROCESS {} · {}", p.pid, p.name);
+    let block = block_pro(&title, theme.accent_main, theme);
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+    let rows = Layout::vertical([Constraint::Min(0), Constraint::Length(1), Constraint::Length(3)]).split(inner);
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), rows[0]);
+
+    // CPU trend, newest on the right; multi-threaded processes can go past 100%
+    let history: Vec<f64> = app.process_cpu_history(p.pid).map(|h| h.iter().map(|v| app.process_cpu(*v) as f64).collect()).unwrap_or_default();
+    let max = history.iter().cloned().fold(100.0, f64::max);
+    let tail = &history[history.len().saturating_sub(rows[2].width as usize)..];
+    let peak = history.iter().cloned().fold(0.0, f64::max);
+    f.render_widget(
+        Paragraph::new(format!("CPU HISTORY · last {}s · peak {:.1}%", history.len(), peak)).style(Style::default().fg(theme.text_dim)),
+        rows[1],
+    );
+    let spark = Sparkline::default()
+        .data(tail.iter().map(|v| v.round() as u64).collect::<Vec<_>>())
+        .max(max.round() as u64)
+        .style(Style::default().fg(theme.accent_main));
+    f.render_widget(spark, rows[2]);
+}
+
+// "3d 04h", "2h 15m" or "4m 09s"
+fn format_run_time(secs: u64) -> String {
+    match secs {
+        s if s >= 86_400 => format!("{}d {:02}h", s / 86_400, (s % 86_400) / 3600),
+        s if s >= 3600 => format!("{}h {:02}m", s / 3600, (s % 3600) / 60),
+        s => format!("{}m {:02}s", s / 60, s % 60),
+    }
+}
+
+// Member PIDs of a name or user group, in the table's current sort order
+fn draw_group_detail(f: &mut Frame, app: &App, theme: &Theme, group: &ProcessGroup, area: Rect) {
+    let header = Row::new(["PID", "CPU", "MEM", "USER", "COMMAND"]).style(Style::default().fg(theme.text_dim)).bottom_margin(1);
+    let rows = group.pids.iter().filter_map(|pid| app.processes.iter().find(|p| p.pid == *pid)).map(|p| {
+        Row::new(vec![
+            ratatui::widgets::Cell::from(p.pid.to_string()).style(Style::default().fg(theme.text_dim)),
+            ratatui::widgets::Cell::from(format!("{:.1}", app.process_cpu(p.cpu))).style(Style::default().fg(theme.accent_main)),
+            ratatui::widgets::Cell::from(format_bytes_compact(p.mem, app.units)),
+            ratatui::widgets::Cell::from(p.user.clone().unwrap_or_else(|| "-".to_string())).style(Style::default().fg(theme.text_dim)),
+            ratatui::widgets::Cell::from(if p.cmd.is_empty() { p.name.clone() } else { p.cmd.clone() }).style(Style::default().fg(theme.text_lite)),
+        ])
+    });
+    let widths = [Constraint::Length(7), Constraint::Length(6), Constraint::Length(7), Constraint::Length(8), Constraint::Min(10)];
+    let kind = if app.group_key == GroupKey::User { "USER" } else { "GROUP" };
+    let title = format!("{} {} · {} PROCESSES · CPU {:.1}% · MEM {}",
+        kind, group.process.name, group.pids.len(), app.process_cpu(group.process.cpu), format_bytes(group.process.mem, app.units));
+    let table = Table::new(rows, widths).header(header).block(block_pro(&title, theme.accent_main, theme));
+    f.render_widget(table, area);
 }
 
-fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
+fn draw_footer(f: &mut Frame, app: &App, theme: &Theme, msg: Option<&str>, area: Rect) {
+    let mut spans: Vec<Span> = app.alerts.active().map(|a| {
+        Span::styled(
+            format!(" ▲ {} {} > {} ", a.rule.metric.label(), a.rule.metric.format(a.value, app.temp_unit), a.rule.metric.format(a.rule.threshold, app.temp_unit)),
+            Style::default().fg(theme.text_lite).bg(theme.accent_crit).add_modifier(Modifier::BOLD),
+        )
+    }).collect();
+    if let Some(msg) = msg {
+        spans.push(Span::styled(format!(" ⚠ {} ", msg), Style::default().fg(theme.accent_warn).add_modifier(Modifier::BOLD)));
+    }
+    let text = Line::from(spans);
+
+    // Right side, from the outside in: the brand, the CPU/NET trend, then --show-stats.
+    // The trend stays in view even when the charts are scrolled away or hidden;
+    // compact mode has its own sparklines.
+    let brand = " ⚡ MK05 ";
+    let spark_width = if area.width >= 80 && app.display_mode == DisplayMode::Full { FOOTER_SPARK_WIDTH } else { 0 };
+    // --show-stats: whether the UI keeps up, and how many samples each frame had to catch up on
+    let stats = app.show_stats.then(|| format!(" UI {:.1}fps • drained {} ", app.diagnostics.fps, app.diagnostics.drained));
+    let cols = Layout::horizontal([
+        Constraint::Min(0),
+        Constraint::Length(stats.as_ref().map_or(0, |s| s.chars().count() as u16)),
+        Constraint::Length(if spark_width > 0 { spark_strip_width(2, spark_width) } else { 0 }),
+        Constraint::Length(brand.chars().count() as u16),
+    ]).split(area);
+    let bg = Style::default().bg(theme.bar_bg);
+    f.render_widget(Paragraph::new(text).alignment(Alignment::Left).style(bg), cols[0]);
+    if let Some(stats) = stats {
+        f.render_widget(Paragraph::new(stats).style(bg.fg(theme.text_dim)), cols[1]);
+    }
+    if spark_width > 0 {
+        let strips = [
+            ("CPU", cpu_trend(app), Some(100), theme.accent_main),
+            ("NET", net_trend(app), None, theme.accent_warn),
+        ];
+        draw_sparkline_strips(f, theme, &strips, cols[2], spark_width);
+    }
+    f.render_widget(Paragraph::new(brand).style(bg.fg(theme.accent_main).add_modifier(Modifier::BOLD)), cols[3]);
+}
+
+fn draw_status_bar(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
     let hostname = sysinfo::System::host_name().unwrap_or_else(|| "Unknown".to_string());
     let uptime = if let Some(s) = &app.last_stats { s.uptime } else { 0 };
     let h = uptime / 3600;
     let m = (uptime % 3600) / 60;
     
-    let text = Line::from(vec![
-        Span::styled(" ⚡ OMNI-MONITOR ", Style::default().fg(C_ACCENT_MAIN).add_modifier(Modifier::BOLD)),
-        Span::styled(format!("| HOST: {} | UPTIME: {:02}h {:02}m ", hostname.to_uppercase(), h, m), Style::default().fg(C_TEXT_DIM)),
-        Span::styled(" | [Q] Quit [S] Sort", Style::default().fg(C_ACCENT_WARN)),
-    ]);
-    
-    f.render_widget(Paragraph::new(text).alignment(Alignment::Left).style(Style::default().bg(Color::Rgb(10,12,20))), area);
+    let mut spans = vec![
+        Span::styled(" ⚡ OMNI-MONITOR ", Style::default().fg(theme.accent_main).add_modifier(Modifier::BOLD)),
+        Span::styled(format!("| HOST: {} | UPTIME: {:02}h {:02}m ", hostname.to_uppercase(), h, m), Style::default().fg(theme.text_dim)),
+        Span::styled(format!("| CPU%: {} ", if app.irix_mode { "IRIX" } else { "SOLARIS" }), Style::default().fg(theme.text_dim)),
+    ];
+    if let Some(bat) = app.last_stats.as_ref().and_then(|s| s.battery.as_ref()) {
+        spans.push(battery_span(bat, theme));
+    }
+    spans.push(Span::styled(" | [Q] Quit [?] Help ", Style::default().fg(theme.accent_warn)));
+
+    // Active alerts take turns in the header, flashing so they catch the eye
+    let active: Vec<_> = app.alerts.active().collect();
+    if !active.is_empty() {
+        let alert = &active[(now_millis() / 2000) as usize % active.len()];
+        let style = if flash_on() {
+            Style::default().fg(theme.text_lite).bg(theme.accent_crit).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.accent_crit).add_modifier(Modifier::BOLD)
+        };
+        let counter = if active.len() > 1 { format!(" (+{})", active.len() - 1) } else { String::new() };
+        spans.push(Span::styled(
+            format!(" ALERT: {} {}{} ", alert.rule.metric.label(), alert.rule.metric.format(alert.value, app.temp_unit), counter),
+            style,
+        ));
+    }
+    let text = Line::from(spans);
+
+    // Sparkline strip on the right, only when there's room for it next to the text
+    let bg = Style::default().bg(theme.bar_bg);
+    let (text_area, spark_area) = if area.width >= 120 && app.display_mode == DisplayMode::Full {
+        let cols = Layout::horizontal([Constraint::Min(0), Constraint::Length(SPARK_STRIP_WIDTH)]).split(area);
+        (cols[0], Some(cols[1]))
+    } else {
+        (area, None)
+    };
+    f.render_widget(Paragraph::new(text).alignment(Alignment::Left).style(bg), text_area);
+    if let Some(spark_area) = spark_area {
+        draw_header_sparklines(f, app, theme, spark_area);
+    }
+}
+
+const SPARK_WIDTH: u16 = 16;
+const SPARK_STRIP_WIDTH: u16 = spark_strip_width(3, SPARK_WIDTH);
+const FOOTER_SPARK_WIDTH: u16 = 12;
+const SPARK_LABEL_WIDTH: u16 = 5;
+
+// Room for `strips` labelled sparklines plus a trailing gap
+const fn spark_strip_width(strips: u16, spark_width: u16) -> u16 {
+    strips * (SPARK_LABEL_WIDTH + spark_width) + 1
+}
+
+// "CPU ▂▃▅ RAM ▅▅▆ NET ▁▁▃" built from the chart histories
+fn draw_header_sparklines(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let ram: Vec<f64> = app.chart_window(&app.ram_history).map(|(_, v)| *v).collect();
+    let strips = [
+        ("CPU", cpu_trend(app), Some(100), theme.accent_main),
+        ("RAM", ram, Some(100), theme.accent_sec),
+        ("NET", net_trend(app), None, theme.accent_warn),
+    ];
+    draw_sparkline_strips(f, theme, &strips, area, SPARK_WIDTH);
+}
+
+fn cpu_trend(app: &App) -> Vec<f64> {
+    app.chart_window(&app.cpu_history_total).map(|(_, v)| *v).collect()
+}
+
+fn net_trend(app: &App) -> Vec<f64> {
+    app.chart_window(&app.net_rx_history).zip(app.chart_window(&app.net_tx_history)).map(|((_, rx), (_, tx))| rx + tx).collect()
+}
+
+// One labelled sparkline per (label, values, max, color), side by side
+fn draw_sparkline_strips(f: &mut Frame, theme: &Theme, strips: &[(&str, Vec<f64>, Option<u64>, Color)], area: Rect, spark_width: u16) {
+    let bg = Style::default().bg(theme.bar_bg);
+    let mut constraints = Vec::new();
+    for _ in strips {
+        constraints.extend([Constraint::Length(SPARK_LABEL_WIDTH), Constraint::Length(spark_width)]);
+    }
+    let cells = Layout::horizontal(constraints).split(area);
+    for (i, (label, values, max, color)) in strips.iter().enumerate() {
+        f.render_widget(Paragraph::new(format!(" {} ", label)).style(bg.fg(theme.text_dim)), cells[i * 2]);
+        let data = downsample(values, spark_width as usize);
+        let mut spark = Sparkline::default().data(&data).style(bg.fg(*color));
+        if let Some(max) = max {
+            spark = spark.max(*max);
+        }
+        f.render_widget(spark, cells[i * 2 + 1]);
+    }
+}
+
+// Averages consecutive samples into at most `width` buckets
+fn downsample(values: &[f64], width: usize) -> Vec<u64> {
+    if values.is_empty() || width == 0 { return Vec::new(); }
+    let bucket = values.len().div_ceil(width);
+    values.chunks(bucket).map(|c| (c.iter().sum::<f64>() / c.len() as f64).round() as u64).collect()
+}
+
+const BUSY_WIDTH: u16 = 16;
+
+// Gauges follow the heatmap scale when a --palette is chosen, otherwise the theme's accents
+fn usage_color(app: &App, theme: &Theme, pct: f64, classic: Color) -> Color {
+    match app.palette {
+        Palette::Classic => classic,
+        _ => theme.load_color(pct.clamp(0.0, 100.0) as u8),
+    }
+}
+
+// " IO ▰▰▱▱▱  42%": how much of the last interval the device was busy
+fn busy_span(app: &App, busy: f32, theme: &Theme) -> Span<'static> {
+    let filled = ((busy / 20.0).round() as usize).min(5);
+    let classic = if busy >= 90.0 { theme.accent_crit } else if busy >= 50.0 { theme.accent_warn } else { theme.text_dim };
+    let color = usage_color(app, theme, busy as f64, classic);
+    Span::styled(format!(" IO {}{} {:>3.0}%", "▰".repeat(filled), "▱".repeat(5 - filled), busy), Style::default().fg(color))
 }
 
-fn draw_content_grid(f: &mut Frame, app: &App, area: Rect) {
+const DISK_TEMP_WIDTH: u16 = 6;
+
+// " 41°C", red once the sensor reports the drive as too hot
+fn disk_temp_span(app: &App, disk: &str, theme: &Theme) -> Span<'static> {
+    match app.disk_health.iter().find(|(n, _)| n == disk) {
+        Some((_, health)) => {
+            let color = if health.warning { theme.accent_crit } else { theme.text_dim };
+            Span::styled(format!(" {:>5}", app.temp_unit.format(health.temp as f64)), Style::default().fg(color))
+        }
+        None => Span::styled("    —", Style::default().fg(theme.text_dim)),
+    }
+}
+
+// Compact text gauge: "| BAT ▰▰▰▱▱ 62% 1h20m"
+fn battery_span(bat: &BatteryInfo, theme: &Theme) -> Span<'static> {
+    let filled = ((bat.percent / 20.0).round() as usize).min(5);
+    let bar = format!("{}{}", "▰".repeat(filled), "▱".repeat(5 - filled));
+    let (icon, remaining) = match bat.state {
+        ChargeState::Charging => ("⚡", bat.time_to_full),
+        ChargeState::Discharging => ("", bat.time_to_empty),
+        _ => ("", None),
+    };
+    let eta = remaining
+        .map(|d| format!(" {}h{:02}m", d.as_secs() / 3600, (d.as_secs() % 3600) / 60))
+        .unwrap_or_default();
+    let color = if bat.percent < 15.0 {
+        theme.accent_crit
+    } else if bat.state == ChargeState::Charging {
+        theme.accent_good
+    } else {
+        theme.text_lite
+    };
+    Span::styled(format!("| BAT {} {:.0}%{}{} ", bar, bat.percent, icon, eta), Style::default().fg(color))
+}
+
+// Compact overview for small panes: a sparkline row per metric, then the top two processes
+fn draw_compact(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let rows = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Length(2),
+        Constraint::Min(0),
+    ]).split(area);
+
+    let stats = app.last_stats.as_ref();
+    let cpu_now = stats.map(|s| s.total_cpu_usage as f64).unwrap_or(0.0);
+    let ram_now = stats.filter(|s| s.ram_total > 0).map(|s| s.ram_used as f64 / s.ram_total as f64 * 100.0).unwrap_or(0.0);
+    let (rx_now, tx_now) = stats.map(|s| (s.rx_speed as f64, s.tx_speed as f64)).unwrap_or_default();
+
+    let cpu: Vec<f64> = app.chart_window(&app.cpu_history_total).map(|(_, v)| *v).collect();
+    let ram: Vec<f64> = app.chart_window(&app.ram_history).map(|(_, v)| *v).collect();
+    let net: Vec<f64> = app.chart_window(&app.net_rx_history).zip(app.chart_window(&app.net_tx_history)).map(|((_, rx), (_, tx))| rx + tx).collect();
+    let metrics = [
+        ("CPU", format!("{:5.1}%", cpu_now), cpu, Some(100), alert_color(app, theme, &[AlertMetric::Cpu], theme.accent_main)),
+        ("RAM", format!("{:5.1}%", ram_now), ram, Some(100), alert_color(app, theme, &[AlertMetric::Ram], theme.accent_sec)),
+        ("NET", format!("↓{}/s ↑{}/s", format_speed(rx_now, app.units), format_speed(tx_now, app.units)), net, None, theme.accent_warn),
+    ];
+    for (row, (label, value, values, max, color)) in rows.iter().zip(metrics) {
+        let cols = Layout::horizontal([Constraint::Length(28), Constraint::Min(0)]).split(*row);
+        let text = Line::from(vec![
+            Span::styled(format!(" {} ", label), Style::default().fg(theme.text_dim)),
+            Span::styled(value, Style::default().fg(color).add_modifier(Modifier::BOLD)),
+        ]);
+        f.render_widget(Paragraph::new(text), cols[0]);
+        let data = downsample(&values, cols[1].width as usize);
+        let mut spark = Sparkline::default().data(&data).style(Style::default().fg(color));
+        if let Some(max) = max {
+            spark = spark.max(max);
+        }
+        f.render_widget(spark, cols[1]);
+    }
+
+    let procs: Vec<Line> = app.processes.iter().take(2).map(|p| Line::from(vec![
+        Span::styled(format!(" {:>7} ", p.pid), Style::default().fg(theme.text_dim)),
+        Span::styled(format!("{:<24}", p.name), Style::default().fg(theme.text_lite)),
+        Span::styled(format!("{:>6.1}% ", app.process_cpu(p.cpu)), Style::default().fg(theme.accent_main)),
+        Span::styled(format!("{:>7}", format_bytes_compact(p.mem, app.units)), Style::default().fg(theme.accent_sec)),
+    ])).collect();
+    f.render_widget(Paragraph::new(procs), rows[3]);
+}
+
+// Below this width the dashboard columns get too thin to read; 80x24 terminals stack instead
+const NARROW_WIDTH: u16 = 100;
+const MIN_SECTION_HEIGHT: u16 = 5; // Don't squeeze a section in below this
+
+type Section = fn(&mut Frame, &App, &Theme, Rect);
+
+fn draw_content_grid(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    if area.width < NARROW_WIDTH {
+        draw_stacked(f, app, theme, area);
+        return;
+    }
+
     // Sidebar (Processes) vs Dashboard
     let main_cols = Layout::default()
         .direction(Direction::Horizontal)
@@ -84,50 +638,186 @@ fn draw_content_grid(f: &mut Frame, app: &App, area: Rect) {
         ].as_ref())
         .split(area);
 
-    draw_sidebar(f, app, main_cols[0]);
-    draw_dashboard(f, app, main_cols[1]);
+    draw_sidebar(f, app, theme, main_cols[0]);
+    draw_dashboard(f, app, theme, main_cols[1]);
 }
 
-fn draw_sidebar(f: &mut Frame, app: &App, area: Rect) {
-    let block = block_pro("ACTIVE TASKS", C_BORDER);
+// Single column for narrow terminals. Scrolls a whole section at a time (Ctrl-e / Ctrl-y)
+// so nothing is ever drawn cut in half; the last section that fits takes the leftover rows.
+fn draw_stacked(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let heatmap_height = (app.cpu_core_history.len() as u16 + 2).clamp(6, 14);
+    let sections: [(u16, Section); STACKED_SECTIONS] = [
+        (10, draw_cpu_section),
+        (heatmap_height, draw_heatmap_section),
+        (14, draw_sidebar),
+        (8, draw_mem_section),
+        (8, draw_net_section),
+        (12, draw_info_section),
+    ];
+    // Clicks must not land on a table that scrolled out of view
+    *app.process_table_layout.borrow_mut() = ProcessTableLayout::default();
+
+    let mut y = area.y;
+    for (height, draw) in &sections[app.page_scroll.min(STACKED_SECTIONS - 1)..] {
+        let remaining = area.bottom().saturating_sub(y);
+        if remaining < MIN_SECTION_HEIGHT.min(*height) {
+            break;
+        }
+        let rect = Rect { y, height: (*height).min(remaining), ..area };
+        draw(f, app, theme, rect);
+        y += rect.height;
+    }
+}
+
+fn draw_sidebar(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let title = if app.process_tree_view {
+        "PROCESS TREE"
+    } else if app.process_grouped && app.group_key == GroupKey::User {
+        "TASKS BY USER"
+    } else if app.process_grouped {
+        "TASKS BY NAME"
+    } else {
+        "ACTIVE TASKS"
+    };
+    let mut title = match &app.user_filter {
+        Some(user) => format!("{} · USER {}", title, user),
+        None => title.to_string(),
+    };
+    if app.hide_kernel {
+        title.push_str(" · NO KERNEL");
+    }
+    if app.sort_locked {
+        title.push_str(" · LOCKED");
+    }
+    let border = if app.focused_panel == FocusPanel::Processes { theme.accent_main } else { theme.border };
+    let block = block_pro(&title, border, theme);
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    // Header
-    let (cpu_c, mem_c) = if app.process_sort_by_cpu { (C_ACCENT_MAIN, C_TEXT_DIM) } else { (C_TEXT_DIM, C_ACCENT_SEC) };
-    let header_cells = vec![
-        ratatui::widgets::Cell::from("PID").style(Style::default().fg(C_TEXT_DIM)),
-        ratatui::widgets::Cell::from("NAME").style(Style::default().fg(C_TEXT_LITE)),
-        ratatui::widgets::Cell::from("CPU").style(Style::default().fg(cpu_c)),
-        ratatui::widgets::Cell::from("MEM").style(Style::default().fg(mem_c)),
-    ];
+    // Header (active sort column is highlighted and carries the direction arrow)
+    let arrow = if app.process_sort_ascending { "▲" } else { "▼" };
+    let header_cell = |label: &str, key: ProcessSortKey, idle: Color, active: Color| {
+        if app.process_sort_key == key {
+            ratatui::widgets::Cell::from(format!("{}{}", label, arrow)).style(Style::default().fg(active).add_modifier(Modifier::BOLD))
+        } else {
+            ratatui::widgets::Cell::from(label.to_string()).style(Style::default().fg(idle))
+        }
+    };
+    let header_cells: Vec<_> = app.columns.iter().map(|&col| {
+        let (idle, active) = column_colors(col, theme);
+        match col.sort_key() {
+            Some(key) => header_cell(col.label(), key, idle, active),
+            None => ratatui::widgets::Cell::from(col.label()).style(Style::default().fg(idle)),
+        }
+    }).collect();
     let header = Row::new(header_cells).height(1).bottom_margin(1);
 
-    // Rows
-    let rows = app.processes.iter().take(40).enumerate().map(|(i, p)| {
-        let style = if i % 2 == 0 { Style::default().bg(Color::Rgb(20, 22, 35)) } else { Style::default() };
-        let cells = vec![
-            ratatui::widgets::Cell::from(p.pid.to_string()).style(Style::default().fg(C_TEXT_DIM)),
-            ratatui::widgets::Cell::from(p.name.clone()).style(Style::default().fg(C_TEXT_LITE)),
-            ratatui::widgets::Cell::from(format!("{:.1}", p.cpu)).style(Style::default().fg(C_ACCENT_MAIN)),
-            ratatui::widgets::Cell::from(format!("{:.0}M", p.mem as f64 / 1024.0 / 1024.0)),
-        ];
+    // Rows (tree view indents names by depth and shows subtree totals)
+    let entries: Vec<(String, &ProcessInfo)> = if app.process_tree_view {
+        app.process_tree.iter().map(|r| {
+            let prefix = if r.depth == 0 { String::new() } else { format!("{}└ ", "  ".repeat(r.depth - 1)) };
+            (format!("{}{}", prefix, r.process.name), &r.process)
+        }).collect()
+    } else if app.process_grouped {
+        app.process_groups.iter().map(|g| {
+            let name = if g.pids.len() > 1 { format!("{} ({})", g.process.name, g.pids.len()) } else { g.process.name.clone() };
+            (name, &g.process)
+        }).collect()
+    } else {
+        app.processes.iter().take(app.listed_processes()).map(|p| (p.name.clone(), p)).collect()
+    };
+
+    let row_count = entries.len();
+    let rows = entries.into_iter().enumerate().map(|(i, (name, p))| {
+        let style = if i % 2 == 0 { Style::default().bg(theme.row_alt_bg) } else { Style::default() };
+        let cells: Vec<_> = app.columns.iter().map(|&col| column_cell(col, &name, p, app, theme)).collect();
         Row::new(cells).style(style).height(1)
     });
 
-    let table = Table::new(rows, [
-        Constraint::Length(6),
-        Constraint::Min(10), // Name flexible
-        Constraint::Length(6),
-        Constraint::Length(6),
-    ]).header(header);
+    let widths: Vec<Constraint> = app.columns.iter().map(|&col| column_width(col)).collect();
+    let table = Table::new(rows, widths.clone()).header(header);
 
     let mut state = TableState::default();
-    state.select(Some(app.process_scroll_state));
-    f.render_stateful_widget(table.highlight_style(Style::default().bg(C_BORDER).add_modifier(Modifier::BOLD)), inner, &mut state);
+    state.select(Some(if app.process_tree_view {
+        app.tree_scroll_state
+    } else if app.process_grouped {
+        app.group_scroll_state
+    } else {
+        app.process_scroll_state
+    }));
+    let mut highlight = Style::default().bg(theme.border).add_modifier(Modifier::BOLD);
+    if theme.plain {
+        highlight = highlight.add_modifier(Modifier::REVERSED);
+    }
+    f.render_stateful_widget(table.row_highlight_style(highlight), inner, &mut state);
+
+    // Same split the table uses internally (Flex::Start, one column of spacing),
+    // recorded so mouse clicks can be mapped back to columns and rows
+    let header_area = Rect { height: 1, ..inner };
+    let cells = Layout::horizontal(widths).flex(Flex::Start).spacing(1).split(header_area);
+    *app.process_table_layout.borrow_mut() = ProcessTableLayout {
+        area: inner,
+        offset: state.offset(),
+        rows: row_count,
+        columns: app.columns.iter().zip(cells.iter()).filter_map(|(col, rect)| Some((col.sort_key()?, *rect))).collect(),
+    };
 }
 
-fn draw_dashboard(f: &mut Frame, app: &App, area: Rect) {
+fn column_width(col: Column) -> Constraint {
+    match col {
+        Column::Pid => Constraint::Length(6),
+        Column::Name => Constraint::Min(10), // Name flexible
+        Column::Cpu | Column::Mem => Constraint::Length(6),
+        Column::Disk => Constraint::Length(10),
+        Column::User => Constraint::Length(8),
+        Column::State => Constraint::Length(8),
+        Column::Start => Constraint::Length(6),
+    }
+}
+
+// (idle, sorted-by) header colors
+fn column_colors(col: Column, theme: &Theme) -> (Color, Color) {
+    match col {
+        Column::Name => (theme.text_lite, theme.accent_warn),
+        Column::Cpu => (theme.text_dim, theme.accent_main),
+        Column::Mem => (theme.text_dim, theme.accent_sec),
+        _ => (theme.text_dim, theme.accent_warn),
+    }
+}
+
+fn column_cell<'a>(col: Column, name: &str, p: &ProcessInfo, app: &App, theme: &Theme) -> ratatui::widgets::Cell<'a> {
+    let dim = Style::default().fg(theme.text_dim);
+    match col {
+        Column::Pid => ratatui::widgets::Cell::from(p.pid.to_string()).style(dim),
+        // Stopped processes (SIGSTOP / 'z') carry a "T" like ps does
+        Column::Name if p.is_stopped() => ratatui::widgets::Cell::from(Line::from(vec![
+            Span::styled("T ", Style::default().fg(theme.accent_warn).add_modifier(Modifier::BOLD)),
+            Span::styled(name.to_string(), Style::default().fg(theme.text_dim)),
+        ])),
+        Column::Name if !app.grouped_by_user() && app.pinned.contains(&p.name) => ratatui::widgets::Cell::from(Line::from(vec![
+            Span::styled("▲ ", Style::default().fg(theme.accent_main).add_modifier(Modifier::BOLD)),
+            Span::styled(name.to_string(), Style::default().fg(theme.text_lite)),
+        ])),
+        Column::Name => ratatui::widgets::Cell::from(name.to_string()).style(Style::default().fg(theme.text_lite)),
+        Column::Cpu => ratatui::widgets::Cell::from(format!("{:.1}", app.process_cpu(p.cpu))).style(Style::default().fg(theme.accent_main)),
+        Column::Mem => ratatui::widgets::Cell::from(format_bytes_compact(p.mem, app.units)),
+        Column::Disk => ratatui::widgets::Cell::from(format_speed((p.disk_read + p.disk_write) as f64, app.units)).style(dim),
+        Column::User => ratatui::widgets::Cell::from(p.user.clone().unwrap_or_else(|| "-".to_string())).style(dim),
+        Column::State => ratatui::widgets::Cell::from(p.status.clone()).style(dim),
+        Column::Start => {
+            // Time of day for today's processes, date for older ones
+            let started = chrono::DateTime::from_timestamp(p.start_time as i64, 0).map(|t| t.with_timezone(&chrono::Local));
+            let text = match started {
+                Some(t) if t.date_naive() == chrono::Local::now().date_naive() => t.format("%H:%M").to_string(),
+                Some(t) => t.format("%b%d").to_string(),
+                None => "-".to_string(),
+            };
+            ratatui::widgets::Cell::from(text).style(dim)
+        }
+    }
+}
+
+fn draw_dashboard(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
     // 3 Rows:
     // 1. CPU Large Chart (40%)
     // 2. Mem & Net (30%)
@@ -143,96 +833,215 @@ fn draw_dashboard(f: &mut Frame, app: &App, area: Rect) {
         .split(area);
 
     // Row 1: CPU
-    draw_cpu_section(f, app, rows[0]);
+    draw_cpu_section(f, app, theme, rows[0]);
 
     // Row 2: Mem + Net
     let row2_cols = Layout::default().direction(Direction::Horizontal).constraints([Constraint::Percentage(40), Constraint::Percentage(60)]).split(rows[1]);
-    draw_mem_section(f, app, row2_cols[0]);
-    draw_net_section(f, app, row2_cols[1]);
+    draw_mem_section(f, app, theme, row2_cols[0]);
+    draw_net_section(f, app, theme, row2_cols[1]);
 
     // Row 3: Heatmap + Sensors/Disk
     let row3_cols = Layout::default().direction(Direction::Horizontal).constraints([Constraint::Percentage(60), Constraint::Percentage(40)]).split(rows[2]);
-    draw_heatmap_section(f, app, row3_cols[0]);
-    draw_info_section(f, app, row3_cols[1]);
+    draw_heatmap_section(f, app, theme, row3_cols[0]);
+    draw_info_section(f, app, theme, row3_cols[1]);
 }
 
-fn draw_cpu_section(f: &mut Frame, app: &App, area: Rect) {
+fn draw_cpu_section(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
     // Title with Load Avg
     let load_str = if let Some(s) = &app.last_stats {
         format!("LOAD: {:.2} {:.2} {:.2}", s.load_avg.0, s.load_avg.1, s.load_avg.2)
     } else { "".to_string() };
     
-    let title = format!("CPU ACTIVITY [{}]", load_str);
-    let block = block_pro(&title, C_ACCENT_MAIN);
+    let smooth_str = if app.smoothing_enabled { format!(" · EMA {:.2}", app.smoothing_alpha) } else { String::new() };
+    let title = format!("CPU ACTIVITY [{}]{}{} · {}", load_str, smooth_str, moving_average_label(app, ChartId::Cpu), app.chart_span_label());
+    let block = block_pro(&title, alert_color(app, theme, &[AlertMetric::Cpu], theme.accent_main), theme);
     let inner = block.inner(area);
     f.render_widget(block, area);
+    let rows = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(inner);
+
+    // Stacked breakdown where the platform reports it ('B'); the single line otherwise
+    let breakdown = app.last_stats.as_ref().and_then(|s| s.cpu_breakdown).filter(|_| app.show_cpu_breakdown && !app.cpu_stack_history[0].is_empty());
+    if let Some(now) = breakdown {
+        let parts = [("USR", now.user, theme.accent_main), ("SYS", now.system, theme.accent_sec), ("IO", now.iowait, theme.accent_warn), ("STEAL", now.steal, theme.accent_crit)];
+        let legend: Vec<Span> = parts.iter()
+            .map(|(label, pct, color)| Span::styled(format!("■ {} {:.0}%  ", label, pct), Style::default().fg(*color)))
+            .collect();
+        f.render_widget(Paragraph::new(Line::from(legend)), rows[0]);
+        let series: Vec<(&VecDeque<(f64, f64)>, Color)> = app.cpu_stack_history.iter().zip(parts.map(|p| p.2)).collect();
+        draw_stacked_chart(f, app, ChartId::Cpu, &series, rows[1], [0.0, 100.0]);
+        return;
+    }
+    f.render_widget(summary_line(&app.cpu_summary, |v| format!("{:.0}%", v), theme), rows[0]);
 
-    draw_chart(f, &app.cpu_history_total, C_ACCENT_MAIN, inner, 0.0, 100.0);
+    let band = app.show_cpu_band.then_some((&app.cpu_history_min, &app.cpu_history_max, theme.border));
+    draw_chart(f, app, (ChartId::Cpu, &app.cpu_history_total), theme.accent_main, band, rows[1], [0.0, 100.0]);
 }
 
-fn draw_mem_section(f: &mut Frame, app: &App, area: Rect) {
-    let block = block_pro("MEMORY", C_ACCENT_SEC);
+fn draw_mem_section(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let title = format!("MEMORY{} · {}", moving_average_label(app, ChartId::Ram), app.chart_span_label());
+    let block = block_pro(&title, alert_color(app, theme, &[AlertMetric::Ram], theme.accent_sec), theme);
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    let chunks = Layout::default().direction(Direction::Vertical).constraints([Constraint::Percentage(80), Constraint::Percentage(20)]).split(inner);
+    let chunks = Layout::vertical([Constraint::Min(0), Constraint::Length(1), Constraint::Length(1), Constraint::Length(1)]).split(inner);
     
-    draw_chart(f, &app.ram_history, C_ACCENT_SEC, chunks[0], 0.0, 100.0);
+    draw_chart(f, app, (ChartId::Ram, &app.ram_history), theme.accent_sec, None, chunks[0], [0.0, 100.0]);
+    if let Some(stats) = &app.last_stats {
+        draw_ram_breakdown(f, app, theme, stats, chunks[1], chunks[2]);
+    }
     
     // Swap Tiny Gauge
     if let Some(stats) = &app.last_stats {
         let ratio = if stats.swap_total > 0 { stats.swap_used as f64 / stats.swap_total as f64 } else { 0.0 };
+        let trend = if app.swap_rate.abs() >= 1024.0 {
+            format!(" {}{}/s", if app.swap_rate > 0.0 { "+" } else { "-" }, format_speed(app.swap_rate.abs(), app.units))
+        } else {
+            String::new()
+        };
+        // Any paging at all means RAM is short right now, however full swap is
+        let paging = stats.swap_in_rate > 0.0 || stats.swap_out_rate > 0.0;
+        let label = if paging {
+            Span::styled(format!("SWP {:.0}%{} · IN {:.0} OUT {:.0} pg/s", ratio * 100.0, trend, stats.swap_in_rate, stats.swap_out_rate),
+                Style::default().fg(theme.accent_crit).add_modifier(Modifier::BOLD))
+        } else {
+            Span::raw(format!("SWP {:.0}%{}", ratio * 100.0, trend))
+        };
         let gauge = Gauge::default()
-            .gauge_style(Style::default().fg(Color::DarkGray).bg(C_PANEL_BG))
+            .gauge_style(Style::default().fg(theme.gauge_dim).bg(theme.panel_bg))
             .ratio(ratio)
-            .label(format!("SWP {:.0}%", ratio * 100.0));
-        f.render_widget(gauge, chunks[1]);
+            .label(label);
+        f.render_widget(gauge, chunks[3]);
     }
 }
 
-fn draw_net_section(f: &mut Frame, app: &App, area: Rect) {
-    let block = block_pro("NETWORK I/O", C_ACCENT_WARN);
+// Stacked bar: in use | reclaimable cache | free, plus a legend line underneath.
+// Platforms that don't report the cache get a plain used / total instead.
+fn draw_ram_breakdown(f: &mut Frame, app: &App, theme: &Theme, stats: &SystemStats, bar: Rect, legend: Rect) {
+    let (used, cache, _) = stats.ram_breakdown();
+    let width = bar.width as u64;
+    let cells = |bytes: u64| (bytes as f64 / stats.ram_total.max(1) as f64 * width as f64).round() as u64;
+    let used_cells = cells(used).min(width);
+    let cache_cells = cells(used + cache).min(width) - used_cells;
+    let free_cells = width - used_cells - cache_cells;
+    let line = Line::from(vec![
+        Span::styled("█".repeat(used_cells as usize), Style::default().fg(theme.accent_sec)),
+        Span::styled("▒".repeat(cache_cells as usize), Style::default().fg(theme.accent_sec)),
+        Span::styled("░".repeat(free_cells as usize), Style::default().fg(theme.gauge_dim)),
+    ]);
+    f.render_widget(Paragraph::new(line), bar);
+
+    let legend_line = if stats.ram_buffers + stats.ram_cached > 0 {
+        Line::from(vec![
+            Span::styled("█ ", Style::default().fg(theme.accent_sec)),
+            Span::styled(format!("USED {}  ", format_bytes(used, app.units)), Style::default().fg(theme.text_lite)),
+            Span::styled("▒ ", Style::default().fg(theme.accent_sec)),
+            Span::styled(format!("CACHE {}  ", format_bytes(cache, app.units)), Style::default().fg(theme.text_dim)),
+            Span::styled(format!("AVAIL {}", format_bytes(stats.ram_available, app.units)), Style::default().fg(theme.text_dim)),
+        ])
+    } else {
+        Line::from(Span::styled(
+            format!("USED {} / {}", format_bytes(used, app.units), format_bytes(stats.ram_total, app.units)),
+            Style::default().fg(theme.text_lite),
+        ))
+    };
+    f.render_widget(Paragraph::new(legend_line), legend);
+}
+
+fn draw_net_section(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let title = format!("NETWORK I/O{} · {}", moving_average_label(app, ChartId::Net), app.chart_span_label());
+    let block = block_pro(&title, theme.accent_warn, theme);
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    let rx: Vec<(f64, f64)> = app.net_rx_history.iter().cloned().collect();
-    let tx: Vec<(f64, f64)> = app.net_tx_history.iter().cloned().collect();
+    let chunks = Layout::default().direction(Direction::Vertical).constraints([Constraint::Length(1), Constraint::Min(0), Constraint::Length(1)]).split(inner);
+    let speed = |v: f64| format!("{}/s", format_speed(v, app.units));
+    let summaries = Line::from(vec![
+        Span::styled("↓ ", Style::default().fg(theme.net_rx)),
+        Span::styled(summary_text(&app.net_rx_summary, speed, false), Style::default().fg(theme.text_dim)),
+        Span::styled("  ↑ ", Style::default().fg(theme.net_tx)),
+        Span::styled(summary_text(&app.net_tx_summary, speed, false), Style::default().fg(theme.text_dim)),
+    ]);
+    f.render_widget(Paragraph::new(summaries), chunks[0]);
+    let totals = Line::from(vec![
+        Span::styled("SESSION ", Style::default().fg(theme.text_dim)),
+        Span::styled(format!("↓ {} ", format_bytes(app.net_session_rx, app.units)), Style::default().fg(theme.net_rx)),
+        Span::styled(format!("↑ {}", format_bytes(app.net_session_tx, app.units)), Style::default().fg(theme.net_tx)),
+    ]);
+    f.render_widget(Paragraph::new(totals), chunks[2]);
+
+    let rx = app.chart_points(ChartId::Net, &app.net_rx_history);
+    let tx = app.chart_points(ChartId::Net, &app.net_tx_history);
     let max = rx.iter().chain(tx.iter()).map(|(_,v)| *v).fold(0.0, f64::max).max(1024.0);
 
-    let datasets = vec![
-        Dataset::default().name("RX").marker(symbols::Marker::Braille).graph_type(GraphType::Line).style(Style::default().fg(Color::Green)).data(&rx),
-        Dataset::default().name("TX").marker(symbols::Marker::Braille).graph_type(GraphType::Line).style(Style::default().fg(Color::Red)).data(&tx),
-    ];
+    let (x_min, x_max) = get_x(&rx);
+    let markers = marker_lines(app, [x_min, x_max], [0.0, max]);
+    let mut datasets = marker_datasets(app, &markers);
+    if app.chart_fill {
+        datasets.extend([fill_dataset(&rx, theme.net_rx), fill_dataset(&tx, theme.net_tx)]);
+    }
+    datasets.extend([
+        Dataset::default().name("RX").marker(symbols::Marker::Braille).graph_type(GraphType::Line).style(Style::default().fg(theme.net_rx)).data(&rx),
+        Dataset::default().name("TX").marker(symbols::Marker::Braille).graph_type(GraphType::Line).style(Style::default().fg(theme.net_tx)).data(&tx),
+    ]);
     
     let chart = Chart::new(datasets)
-        .x_axis(Axis::default().bounds([get_x(&rx).0, get_x(&rx).1]))
-        .y_axis(Axis::default().bounds([0.0, max]).labels(vec![Span::raw("0"), Span::raw("MAX")]));
-    f.render_widget(chart, inner);
+        .x_axis(Axis::default().bounds([x_min, x_max]))
+        .y_axis(Axis::default().bounds([0.0, max]).labels(vec![Span::raw("0"), Span::raw(format!("{}/s", format_speed(max, app.units)))]));
+    f.render_widget(chart, chunks[1]);
 }
 
-fn draw_heatmap_section(f: &mut Frame, app: &App, area: Rect) {
-    let block = block_pro("CORE MATRIX", C_TEXT_DIM);
+fn draw_heatmap_section(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    // Hybrid CPUs: P-cores and E-cores get their own groups
+    let (mut p_rows, mut e_rows): (Vec<&VecDeque<u8>>, Vec<&VecDeque<u8>>) = (Vec::new(), Vec::new());
+    for (id, history) in app.cpu_core_ids.iter().zip(app.cpu_core_history.iter()) {
+        match app.core_types.get(id) {
+            Some(CoreType::Efficiency) => e_rows.push(history),
+            _ => p_rows.push(history),
+        }
+    }
+    let grouped = !app.core_types.is_empty() && !p_rows.is_empty() && !e_rows.is_empty();
+
+    let title = if grouped { format!("CORE MATRIX · {}P + {}E", p_rows.len(), e_rows.len()) } else { "CORE MATRIX".to_string() };
+    let block = block_pro(&title, theme.text_dim, theme);
     let inner = block.inner(area);
     f.render_widget(block, area);
+
+    // History length follows the canvas width; takes effect on the next chart update
+    app.heatmap_columns.set(inner.width as usize);
     
-    let core_count = app.cpu_core_history.len();
-    if core_count == 0 { return; }
+    if app.cpu_core_history.is_empty() { return; }
 
+    if !grouped {
+        let rows: Vec<&VecDeque<u8>> = app.cpu_core_history.iter().collect();
+        draw_core_rows(f, app, theme, &rows, inner);
+        return;
+    }
+
+    let parts = Layout::vertical([
+        Constraint::Fill(p_rows.len() as u16),
+        Constraint::Length(1),
+        Constraint::Fill(e_rows.len() as u16),
+    ]).split(inner);
+    draw_core_rows(f, app, theme, &p_rows, parts[0]);
+    let separator = Paragraph::new("▲ P-CORES ─── E-CORES ▼").alignment(Alignment::Center).style(Style::default().fg(theme.text_dim));
+    f.render_widget(separator, parts[1]);
+    draw_core_rows(f, app, theme, &e_rows, parts[2]);
+}
+
+fn draw_core_rows(f: &mut Frame, app: &App, theme: &Theme, rows: &[&VecDeque<u8>], area: Rect) {
+    let core_count = rows.len();
+    let width = app.heatmap_width() as f64;
     let canvas = Canvas::default()
-        .x_bounds([0.0, 100.0])
+        .x_bounds([0.0, width])
         .y_bounds([0.0, core_count as f64])
         .paint(|ctx| {
-            for (core_idx, history) in app.cpu_core_history.iter().enumerate() {
+            for (core_idx, history) in rows.iter().enumerate() {
                 for (time_idx, &load) in history.iter().enumerate() {
-                    // Gradient: Dark Blue -> Cyan -> Green -> Yellow -> Red
-                    let color = match load {
-                        0..=5 => Color::Rgb(15, 20, 30),
-                        6..=20 => Color::Rgb(0, 50, 100),
-                        21..=40 => Color::Rgb(0, 150, 150),
-                        41..=60 => Color::Rgb(0, 255, 100),
-                        61..=80 => Color::Rgb(200, 200, 0),
-                        _ => Color::Rgb(255, 0, 50),
-                    };
+                    // Without color every cell would look the same; only mark busy ones
+                    if theme.plain && load <= theme.heat_buckets[2] {
+                        continue;
+                    }
+                    let color = theme.load_color(load);
                     ctx.draw(&Rectangle {
                         x: time_idx as f64,
                         y: (core_count - 1 - core_idx) as f64, 
@@ -241,45 +1050,257 @@ fn draw_heatmap_section(f: &mut Frame, app: &App, area: Rect) {
                 }
             }
         });
-    f.render_widget(canvas, inner);
+    f.render_widget(canvas, area);
 }
 
-fn draw_info_section(f: &mut Frame, app: &App, area: Rect) {
-    let block = block_pro("SYSTEM STATUS", C_TEXT_DIM);
+fn draw_info_section(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    let title = format!("SYSTEM STATUS · TEMP{} {}", moving_average_label(app, ChartId::Temp), app.chart_span_label());
+    let idle = if app.focused_panel == FocusPanel::Disks { theme.accent_main } else { theme.text_dim };
+    let block = block_pro(&title, alert_color(app, theme, &[AlertMetric::Temp, AlertMetric::Disk], idle), theme);
     let inner = block.inner(area);
     f.render_widget(block, area);
 
     let chunks = Layout::default().direction(Direction::Vertical).constraints([Constraint::Percentage(50), Constraint::Percentage(50)]).split(inner);
 
     // Temp Chart
-    draw_chart(f, &app.temp_history, C_ACCENT_CRIT, chunks[0], 0.0, 100.0);
-
-    // Disk Gauges
-    let disk_constraints = vec![Constraint::Length(1); app.disks.len().min(3)];
-    let disk_layout = Layout::default().direction(Direction::Vertical).constraints(disk_constraints).split(chunks[1]);
-    for (i, (name, used, total)) in app.disks.iter().take(3).enumerate() {
-        if i >= disk_layout.len() { break; }
-        let ratio = *used as f64 / *total as f64;
-        let color = if ratio > 0.8 { C_ACCENT_CRIT } else { C_ACCENT_MAIN };
+    if app.temps.is_empty() {
+        let placeholder = Paragraph::new("NO SENSORS").alignment(Alignment::Center).style(Style::default().fg(theme.text_dim));
+        let mid = Rect { y: chunks[0].y + chunks[0].height / 2, height: 1.min(chunks[0].height), ..chunks[0] };
+        f.render_widget(placeholder, mid);
+    } else {
+        let rows = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(chunks[0]);
+        let empty = VecDeque::new();
+        let reading = |name: &str| app.temps.iter().find(|t| t.name == name).map(|t| t.celsius);
+        match &app.temp_view {
+            TempView::Max => {
+                f.render_widget(summary_line(&app.temp_summary, |v| app.temp_unit.format(v), theme), rows[0]);
+                draw_chart(f, app, (ChartId::Temp, &app.temp_history), theme.accent_crit, None, rows[1], [0.0, 100.0]);
+            }
+            TempView::Sensor(name) => {
+                let now = reading(name).map(|t| format!(" {}", app.temp_unit.format(t as f64))).unwrap_or_else(|| " --".to_string());
+                let line = Line::from(vec![
+                    Span::styled(name.clone(), Style::default().fg(theme.text_lite)),
+                    Span::styled(now, Style::default().fg(theme.accent_crit)),
+                ]);
+                f.render_widget(Paragraph::new(line), rows[0]);
+                draw_chart(f, app, (ChartId::Temp, app.sensor_history.get(name).unwrap_or(&empty)), theme.accent_crit, None, rows[1], [0.0, 100.0]);
+            }
+            TempView::Hottest => {
+                let colors = [theme.accent_crit, theme.accent_warn, theme.accent_main];
+                let series: Vec<(&str, Color)> = app.hottest_sensors(TEMP_OVERLAY_COUNT).into_iter().zip(colors).collect();
+                let legend: Vec<Span> = series.iter()
+                    .map(|(name, color)| Span::styled(format!("■ {} {}  ", name, app.temp_unit.format(reading(name).unwrap_or(0.0) as f64)), Style::default().fg(*color)))
+                    .collect();
+                f.render_widget(Paragraph::new(Line::from(legend)), rows[0]);
+                let data: Vec<(&VecDeque<(f64, f64)>, Color)> = series.iter()
+                    .map(|(name, color)| (app.sensor_history.get(*name).unwrap_or(&empty), *color))
+                    .collect();
+                draw_overlay_chart(f, app, ChartId::Temp, &data, rows[1], [0.0, 100.0]);
+            }
+        }
+    }
+
+    // Swap pressure warning takes the first line above the disks
+    let mut disk_area = chunks[1];
+    if let Some(pct) = app.swap_pressure() {
+        let rows = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(chunks[1]);
+        let warning = format!("⚠ SWAPPING: {:.0}% used, +{}/s", pct, format_speed(app.swap_rate, app.units));
+        f.render_widget(Paragraph::new(warning).style(Style::default().fg(theme.accent_warn).add_modifier(Modifier::BOLD)), rows[0]);
+        disk_area = rows[1];
+    }
+
+    // TCP connection breakdown, where the platform exposes it
+    if let Some(conns) = app.last_stats.as_ref().and_then(|s| s.connections) {
+        let rows = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(disk_area);
+        let line = Line::from(vec![
+            Span::styled("TCP ", Style::default().fg(theme.text_dim)),
+            Span::styled(format!("EST {}", conns.established), Style::default().fg(theme.accent_main)),
+            Span::styled(format!(" · TW {}", conns.time_wait), Style::default().fg(theme.text_dim)),
+            Span::styled(format!(" · LISTEN {}", conns.listen), Style::default().fg(theme.text_dim)),
+            Span::styled(format!(" · OTHER {}", conns.other), Style::default().fg(theme.text_dim)),
+        ]);
+        f.render_widget(Paragraph::new(line), rows[0]);
+        disk_area = rows[1];
+    }
+
+    // Scheduler activity, where the platform exposes it
+    if let Some((ctxt, intr)) = app.last_stats.as_ref().and_then(|s| s.ctx_switch_rate.zip(s.interrupt_rate)) {
+        let rows = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(disk_area);
+        let line = Line::from(vec![
+            Span::styled("KERNEL ", Style::default().fg(theme.text_dim)),
+            Span::styled(format!("CTXSW {}/s", format_count(ctxt)), Style::default().fg(theme.accent_main)),
+            Span::styled(format!(" · INTR {}/s", format_count(intr)), Style::default().fg(theme.text_dim)),
+        ]);
+        f.render_widget(Paragraph::new(line), rows[0]);
+        disk_area = rows[1];
+    }
+
+    // Which machine this is
+    if let Some(cpu) = app.last_stats.as_ref().and_then(|s| s.cpu_label()) {
+        let rows = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(disk_area);
+        let line = Line::from(vec![
+            Span::styled("CPU ", Style::default().fg(theme.text_dim)),
+            Span::styled(cpu, Style::default().fg(theme.text_lite)),
+        ]);
+        f.render_widget(Paragraph::new(line), rows[0]);
+        disk_area = rows[1];
+    }
+
+    // What the session has seen so far
+    {
+        let rows = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(disk_area);
+        let line = Line::from(vec![
+            Span::styled(format!("SESSION {} ", app.session_duration_label()), Style::default().fg(theme.text_dim)),
+            Span::styled(format!("↓ {} ", format_bytes(app.net_session_rx, app.units)), Style::default().fg(theme.net_rx)),
+            Span::styled(format!("↑ {}", format_bytes(app.net_session_tx, app.units)), Style::default().fg(theme.net_tx)),
+            Span::styled(" · PEAK ", Style::default().fg(theme.text_dim)),
+            Span::styled(format!("CPU {:.0}% ", app.peak_cpu), Style::default().fg(theme.accent_main)),
+            Span::styled(format!("RAM {:.0}%", app.peak_ram), Style::default().fg(theme.accent_sec)),
+        ]);
+        f.render_widget(Paragraph::new(line), rows[0]);
+        disk_area = rows[1];
+    }
+
+    // Disk Gauges: one row each, as many as fit. When some don't, the last row says
+    // which ones are shown and '[' / ']' scroll.
+    let height = disk_area.height as usize;
+    let rows = if app.disks.len() > height { height.saturating_sub(1) } else { app.disks.len() };
+    app.disk_rows.set(rows);
+    let first = app.disk_scroll.min(app.disks.len() - rows);
+    let disk_layout = Layout::vertical(vec![Constraint::Length(1); height]).split(disk_area);
+    if rows < app.disks.len() && height > 0 {
+        let more = format!("↕ {}-{} of {} disks · [ ] scroll", first + 1, first + rows, app.disks.len());
+        f.render_widget(Paragraph::new(more).style(Style::default().fg(theme.text_dim)), disk_layout[height - 1]);
+    }
+    for (row, (i, disk)) in app.disks.iter().enumerate().skip(first).take(rows).enumerate() {
+        let name = &disk.name;
+        let ratio = disk.used as f64 / disk.total as f64;
+        let color = usage_color(app, theme, ratio * 100.0, if ratio > 0.8 { theme.accent_crit } else { theme.accent_main });
+        let io = match app.disk_io.get(i) {
+            Some(io) => format!("  R {}/s  W {}/s", format_speed(io.read as f64, app.units), format_speed(io.write as f64, app.units)),
+            None => String::new(),
+        };
         let gauge = Gauge::default()
-            .gauge_style(Style::default().fg(color).bg(C_BG))
+            .gauge_style(Style::default().fg(color).bg(theme.bg))
             .ratio(ratio)
-            .label(format!("{} {:.0}%", name, ratio * 100.0));
-        f.render_widget(gauge, disk_layout[i]);
+            .label(format!("{} on {} ({}) {:.0}%{}", name, disk.mount, disk.fs, ratio * 100.0, io));
+        // Drive temperature, "—" when no sensor could be matched to this disk
+        let cols = Layout::horizontal([Constraint::Min(0), Constraint::Length(DISK_TEMP_WIDTH)]).split(disk_layout[row]);
+        f.render_widget(Paragraph::new(disk_temp_span(app, name, theme)), cols[1]);
+        // Busy % sits to the right of the usage gauge where the platform reports it
+        let mut row = cols[0];
+        if let Some((_, busy)) = app.disk_busy.iter().find(|(n, _)| n == name) {
+            let cols = Layout::horizontal([Constraint::Min(0), Constraint::Length(BUSY_WIDTH)]).split(row);
+            f.render_widget(Paragraph::new(busy_span(app, *busy, theme)), cols[1]);
+            row = cols[0];
+        }
+        f.render_widget(gauge, row);
     }
 }
 
-fn draw_chart(f: &mut Frame, data: &std::collections::VecDeque<(f64, f64)>, color: Color, area: Rect, min: f64, max: f64) {
-    let vec_data: Vec<(f64, f64)> = data.iter().cloned().collect();
+// "min 3% • avg 34% • max 98%" since launch (or the last 'r'). Network rates
+// skip the minimum, which is nearly always zero and costs width.
+fn summary_text(stats: &RunningStats, fmt: impl Fn(f64) -> String, with_min: bool) -> String {
+    match stats.avg() {
+        Some(avg) if with_min => format!("min {} • avg {} • max {}", fmt(stats.min), fmt(avg), fmt(stats.max)),
+        Some(avg) => format!("avg {} • max {}", fmt(avg), fmt(stats.max)),
+        None => "collecting…".to_string(),
+    }
+}
+
+fn summary_line<'a>(stats: &RunningStats, fmt: impl Fn(f64) -> String, theme: &Theme) -> Paragraph<'a> {
+    Paragraph::new(summary_text(stats, fmt, true)).style(Style::default().fg(theme.text_dim))
+}
+
+// Min/max series and their color, drawn as a shaded band behind the main line
+type Band<'a> = (&'a VecDeque<(f64, f64)>, &'a VecDeque<(f64, f64)>, Color);
+
+// Only the points inside the selected time span are rendered (moving-averaged if that's on
+// for `chart`); the x-bounds follow that slice
+fn draw_chart(f: &mut Frame, app: &App, (chart, data): (ChartId, &VecDeque<(f64, f64)>), color: Color, band: Option<Band>, area: Rect, [min, max]: [f64; 2]) {
+    let vec_data = app.chart_points(chart, data);
     let (x_min, x_max) = get_x(&vec_data);
 
-    let datasets = vec![
-        Dataset::default().marker(symbols::Marker::Braille).graph_type(GraphType::Line).style(Style::default().fg(color)).data(&vec_data),
-    ];
-    let chart = Chart::new(datasets)
+    // Zig-zag min -> max -> next min: with braille dots the strokes fill the band
+    let band_data: Vec<(f64, f64)> = band.iter()
+        .flat_map(|(lo, hi, _)| app.chart_window(lo).zip(app.chart_window(hi)).flat_map(|(l, h)| [*l, *h]))
+        .collect();
+
+    let markers = marker_lines(app, [x_min, x_max], [min, max]);
+    let mut datasets = marker_datasets(app, &markers);
+    if app.chart_fill {
+        datasets.push(fill_dataset(&vec_data, color));
+    }
+    if let Some((_, _, band_color)) = band {
+        datasets.push(Dataset::default().marker(symbols::Marker::Braille).graph_type(GraphType::Line).style(Style::default().fg(band_color)).data(&band_data));
+    }
+    datasets.push(Dataset::default().marker(symbols::Marker::Braille).graph_type(GraphType::Line).style(Style::default().fg(color)).data(&vec_data));
+    let widget = Chart::new(datasets)
+        .x_axis(Axis::default().bounds([x_min, x_max]))
+        .y_axis(Axis::default().bounds([min, max]).labels(y_labels(app, chart, [min, max])));
+    f.render_widget(widget, area);
+}
+
+// Several series on shared axes; x bounds cover the longest of them
+fn draw_overlay_chart(f: &mut Frame, app: &App, chart: ChartId, series: &[(&VecDeque<(f64, f64)>, Color)], area: Rect, [min, max]: [f64; 2]) {
+    let windows: Vec<Vec<(f64, f64)>> = series.iter().map(|(data, _)| app.chart_points(chart, data)).collect();
+    let x_min = windows.iter().filter_map(|w| w.first().map(|p| p.0)).reduce(f64::min).unwrap_or(0.0);
+    let x_max = windows.iter().filter_map(|w| w.last().map(|p| p.0)).reduce(f64::max).unwrap_or(0.0).max(x_min + 10.0);
+    let markers = marker_lines(app, [x_min, x_max], [min, max]);
+    let mut datasets = marker_datasets(app, &markers);
+    datasets.extend(windows.iter().zip(series)
+        .map(|(points, (_, color))| Dataset::default().marker(symbols::Marker::Braille).graph_type(GraphType::Line).style(Style::default().fg(*color)).data(points)));
+    let widget = Chart::new(datasets)
         .x_axis(Axis::default().bounds([x_min, x_max]))
-        .y_axis(Axis::default().bounds([min, max]).labels(vec![Span::raw(format!("{:.0}", min)), Span::raw(format!("{:.0}", max))]));
-    f.render_widget(chart, area);
+        .y_axis(Axis::default().bounds([min, max]).labels(y_labels(app, chart, [min, max])));
+    f.render_widget(widget, area);
+}
+
+// Filled areas for running totals (each series the top edge of its band). The tallest
+// is drawn first so every band shows through above the one below it.
+fn draw_stacked_chart(f: &mut Frame, app: &App, chart: ChartId, series: &[(&VecDeque<(f64, f64)>, Color)], area: Rect, [min, max]: [f64; 2]) {
+    let windows: Vec<Vec<(f64, f64)>> = series.iter().map(|(data, _)| app.chart_points(chart, data)).collect();
+    let (x_min, x_max) = get_x(windows.first().map_or(&[][..], |w| w.as_slice()));
+    let markers = marker_lines(app, [x_min, x_max], [min, max]);
+    let mut datasets = marker_datasets(app, &markers);
+    datasets.extend(windows.iter().zip(series).rev().map(|(points, (_, color))| {
+        Dataset::default().marker(symbols::Marker::Braille).graph_type(GraphType::Bar).style(Style::default().fg(*color)).data(points)
+    }));
+    let widget = Chart::new(datasets)
+        .x_axis(Axis::default().bounds([x_min, x_max]))
+        .y_axis(Axis::default().bounds([min, max]).labels(y_labels(app, chart, [min, max])));
+    f.render_widget(widget, area);
+}
+
+// Temperatures are charted in Celsius; only the axis labels follow the chosen unit
+fn y_labels(app: &App, chart: ChartId, [min, max]: [f64; 2]) -> Vec<Span<'static>> {
+    let label = |v: f64| match chart {
+        ChartId::Temp => format!("{:.0}", app.temp_unit.convert(v)),
+        _ => format!("{:.0}", v),
+    };
+    vec![Span::raw(label(min)), Span::raw(label(max))]
+}
+
+// " · MA 5" in a chart title while its line is a moving average
+fn moving_average_label(app: &App, chart: ChartId) -> String {
+    app.moving_average_for(chart).map(|n| format!(" · MA {}", n)).unwrap_or_default()
+}
+
+// One bar per point from the x-axis up to the value; at chart density the bars
+// merge into a solid area under the line
+fn fill_dataset(points: &[(f64, f64)], color: Color) -> Dataset<'_> {
+    Dataset::default().marker(symbols::Marker::Braille).graph_type(GraphType::Bar).style(Style::default().fg(color).add_modifier(Modifier::DIM)).data(points)
+}
+
+// Vertical strokes at the 'a' markers that fall inside the visible x range
+fn marker_lines(app: &App, [x_min, x_max]: [f64; 2], [min, max]: [f64; 2]) -> Vec<[(f64, f64); 2]> {
+    app.chart_markers.iter().filter(|m| (x_min..=x_max).contains(*m)).map(|&m| [(m, min), (m, max)]).collect()
+}
+
+// Drawn first so the data lines stay on top
+fn marker_datasets<'a>(app: &App, lines: &'a [[(f64, f64); 2]]) -> Vec<Dataset<'a>> {
+    let color = app.theme().accent_warn;
+    lines.iter().map(|line| Dataset::default().marker(symbols::Marker::Braille).graph_type(GraphType::Line).style(Style::default().fg(color)).data(line)).collect()
 }
 
 fn get_x(data: &[(f64, f64)]) -> (f64, f64) {
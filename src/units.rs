@@ -0,0 +1,164 @@
+use anyhow::{bail, Result};
+
+// How byte quantities are scaled and labeled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnitMode {
+    #[default]
+    Binary, // 1024-based: KiB, MiB, GiB
+    Si,     // 1000-based: KB, MB, GB
+}
+
+impl UnitMode {
+    pub fn parse(name: &str) -> Result<Self> {
+        Ok(match name.to_lowercase().as_str() {
+            "binary" | "iec" => UnitMode::Binary,
+            "si" | "decimal" => UnitMode::Si,
+            other => bail!("unknown unit mode '{}' (expected si or binary)", other),
+        })
+    }
+
+    fn scale(self, bytes: f64) -> (f64, &'static str) {
+        let (base, labels) = match self {
+            UnitMode::Binary => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB"]),
+            UnitMode::Si => (1000.0, ["B", "KB", "MB", "GB", "TB"]),
+        };
+        let mut value = bytes;
+        let mut unit = 0;
+        while value >= base && unit < labels.len() - 1 {
+            value /= base;
+            unit += 1;
+        }
+        (value, labels[unit])
+    }
+}
+
+// Temperatures are sampled and stored in Celsius; this only picks how they are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TempUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+impl TempUnit {
+    pub fn parse(name: &str) -> Result<Self> {
+        Ok(match name.to_lowercase().as_str() {
+            "c" | "celsius" => TempUnit::Celsius,
+            "f" | "fahrenheit" => TempUnit::Fahrenheit,
+            other => bail!("unknown temperature unit '{}' (expected c or f)", other),
+        })
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            TempUnit::Celsius => TempUnit::Fahrenheit,
+            TempUnit::Fahrenheit => TempUnit::Celsius,
+        }
+    }
+
+    pub fn suffix(self) -> &'static str {
+        match self {
+            TempUnit::Celsius => "°C",
+            TempUnit::Fahrenheit => "°F",
+        }
+    }
+
+    pub fn convert(self, celsius: f64) -> f64 {
+        match self {
+            TempUnit::Celsius => celsius,
+            TempUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+
+    pub fn to_celsius(self, value: f64) -> f64 {
+        match self {
+            TempUnit::Celsius => value,
+            TempUnit::Fahrenheit => (value - 32.0) * 5.0 / 9.0,
+        }
+    }
+
+    // "72°C" / "162°F"
+    pub fn format(self, celsius: f64) -> String {
+        format!("{:.0}{}", self.convert(celsius), self.suffix())
+    }
+}
+
+// Rates and other fractional quantities; callers append "/s" where it applies
+pub fn format_speed(bytes: f64, mode: UnitMode) -> String {
+    match mode.scale(bytes) {
+        (v, "B") => format!("{:.0} B", v),
+        (v, unit) => format!("{:.1} {}", v, unit),
+    }
+}
+
+// Totals (session traffic, cumulative I/O)
+pub fn format_bytes(bytes: u64, mode: UnitMode) -> String {
+    match mode.scale(bytes as f64) {
+        (_, "B") => format!("{} B", bytes),
+        (v, unit @ ("KiB" | "KB" | "MiB" | "MB")) => format!("{:.1} {}", v, unit),
+        (v, unit) => format!("{:.2} {}", v, unit),
+    }
+}
+
+// Narrow table cells (process MEM): "358M", "1.2G"; the scale still follows the mode
+pub fn format_bytes_compact(bytes: u64, mode: UnitMode) -> String {
+    match mode.scale(bytes as f64) {
+        (_, "B") => format!("{}B", bytes),
+        (v, unit) if v < 10.0 => format!("{:.1}{}", v, &unit[..1]),
+        (v, unit) => format!("{:.0}{}", v, &unit[..1]),
+    }
+}
+
+// Event rates and counts: "950", "12.3k", "4.1M"
+pub fn format_count(n: f64) -> String {
+    match n {
+        n if n >= 1e6 => format!("{:.1}M", n / 1e6),
+        n if n >= 1e3 => format!("{:.1}k", n / 1e3),
+        n => format!("{:.0}", n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_rate_in_both_modes() {
+        assert_eq!(format_speed(1_500_000.0, UnitMode::Si), "1.5 MB");
+        assert_eq!(format_speed(1_500_000.0, UnitMode::Binary), "1.4 MiB");
+        assert_eq!(format_speed(999.0, UnitMode::Si), "999 B");
+        assert_eq!(format_speed(1000.0, UnitMode::Binary), "1000 B");
+    }
+
+    #[test]
+    fn formats_totals_in_both_modes() {
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024, UnitMode::Binary), "3.00 GiB");
+        assert_eq!(format_bytes(2_500_000_000, UnitMode::Si), "2.50 GB");
+        assert_eq!(format_bytes(512, UnitMode::Si), "512 B");
+    }
+
+    #[test]
+    fn formats_compact_sizes_in_both_modes() {
+        assert_eq!(format_bytes_compact(358 * 1024 * 1024, UnitMode::Binary), "358M");
+        assert_eq!(format_bytes_compact(358 * 1024 * 1024, UnitMode::Si), "375M");
+        assert_eq!(format_bytes_compact(1_250_000_000, UnitMode::Si), "1.2G");
+        assert_eq!(format_bytes_compact(900, UnitMode::Binary), "900B");
+    }
+
+    #[test]
+    fn formats_counts_with_suffixes() {
+        assert_eq!(format_count(950.0), "950");
+        assert_eq!(format_count(12_340.0), "12.3k");
+        assert_eq!(format_count(4_100_000.0), "4.1M");
+    }
+
+    #[test]
+    fn temperatures_convert_both_ways() {
+        assert_eq!(TempUnit::Fahrenheit.convert(100.0), 212.0);
+        assert_eq!(TempUnit::Fahrenheit.to_celsius(32.0), 0.0);
+        assert_eq!(TempUnit::Celsius.format(71.6), "72°C");
+        assert_eq!(TempUnit::Fahrenheit.format(85.0), "185°F");
+        assert_eq!(TempUnit::parse("F").unwrap(), TempUnit::Fahrenheit);
+        assert!(TempUnit::parse("k").is_err());
+    }
+}